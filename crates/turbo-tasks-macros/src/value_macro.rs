@@ -336,6 +336,10 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         /// Places a value in a cell of the current task.
         ///
         /// Cell is selected by the provided `key`. `key` must not be used twice during the current task.
+        ///
+        /// Prefer this over `cell` when the number or order of cells varies
+        /// between executions, e. g. when cells are created in branches or
+        /// loops, so that a value keeps its cell across recomputations.
         #cell_prefix fn keyed_cell<
             K: std::fmt::Debug + std::cmp::Eq + std::cmp::Ord + std::hash::Hash + turbo_tasks::Typed + turbo_tasks::TypedForInput + Send + Sync + 'static,
         >(key: K, content: #cell_arg_type) -> #ref_ident {
@@ -358,6 +362,10 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         /// Places a value in a cell of the current task.
         ///
         /// Cell is selected by the provided `key`. `key` must not be used twice during the current task.
+        ///
+        /// Prefer this over `cell` when the number or order of cells varies
+        /// between executions, e. g. when cells are created in branches or
+        /// loops, so that a value keeps its cell across recomputations.
         #cell_prefix fn keyed_cell<
             K: std::fmt::Debug + std::cmp::Eq + std::cmp::Ord + std::hash::Hash + turbo_tasks::Typed + turbo_tasks::TypedForInput + Send + Sync + 'static,
         >(self, key: K) -> #ref_ident {
@@ -503,6 +511,92 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
         }
     };
 
+    let try_get_sync = {
+        let read = if let Some(inner_type) = inner_type {
+            quote! {
+                /// SAFETY: Types are binary identical via #[repr(transparent)]
+                unsafe { self.node.try_transparent_read_sync_untracked::<#ident, #inner_type>(turbo_tasks) }
+            }
+        } else {
+            quote! {
+                self.node.try_read_sync_untracked::<#ident>(turbo_tasks)
+            }
+        };
+        quote! {
+            /// Returns the value when it is already computed, without waiting and without
+            /// driving any computation. Returns `None` otherwise.
+            ///
+            /// see [turbo_tasks::RawVc::try_read_sync_untracked]
+            pub fn try_get_sync(self, turbo_tasks: &dyn turbo_tasks::TurboTasksApi) -> Option<#read_ref_ident> {
+                #read
+            }
+        }
+    };
+
+    let get_or = {
+        let (default_type, read_default) = if let Some(inner_type) = inner_type {
+            (
+                quote! { #inner_type },
+                quote! {
+                    /// SAFETY: Types are binary identical via #[repr(transparent)]
+                    unsafe { turbo_tasks::ReadRef::new_transparent(std::sync::Arc::new(#ident(default()))) }
+                },
+            )
+        } else {
+            (
+                quote! { #ident },
+                quote! {
+                    turbo_tasks::ReadRef::new(std::sync::Arc::new(default()))
+                },
+            )
+        };
+        quote! {
+            /// Reads the value like `.await`, but falls back to the value returned by
+            /// `default` when the task failed. The dependency is still tracked, so the
+            /// current task will recompute once the error is fixed.
+            pub async fn get_or(self, default: impl FnOnce() -> #default_type) -> #read_ref_ident {
+                match self.await {
+                    Ok(value) => value,
+                    Err(_) => #read_default,
+                }
+            }
+        }
+    };
+
+    let inspect = {
+        let value_type = if let Some(inner_type) = inner_type {
+            quote! { #inner_type }
+        } else {
+            quote! { #ident }
+        };
+        quote! {
+            /// Reads the value, passes it to `f` (e. g. to log it) and returns the
+            /// same Vc. The read is tracked like `.await`, so it's useful to trace
+            /// values flowing through a pipeline while debugging.
+            pub async fn inspect(self, f: impl FnOnce(&#value_type)) -> turbo_tasks::Result<Self> {
+                f(&*self.await?);
+                Ok(self)
+            }
+        }
+    };
+
+    let value_eq = if manual_eq {
+        quote! {}
+    } else {
+        quote! {
+            /// Compares the values of both Vcs. Unlike `==`, which compares the cells
+            /// they point to, two distinct cells holding equal values are considered
+            /// equal. This reads both values, so it's more expensive than `==` and the
+            /// current task will depend on both cells.
+            pub async fn value_eq(self, other: Self) -> turbo_tasks::Result<bool> {
+                if self == other {
+                    return Ok(true);
+                }
+                Ok(*self.await? == *other.await?)
+            }
+        }
+    };
+
     let value_debug_impl = if inner_type.is_some() {
         // For transparent values, we defer directly to the inner type's `ValueDebug`
         // implementation.
@@ -653,6 +747,14 @@ pub fn value(args: TokenStream, input: TokenStream) -> TokenStream {
             }
 
             #strongly_consistent
+
+            #try_get_sync
+
+            #get_or
+
+            #inspect
+
+            #value_eq
         }
 
         impl turbo_tasks::CollectiblesSource for #ref_ident {
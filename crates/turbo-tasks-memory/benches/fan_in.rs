@@ -0,0 +1,62 @@
+use anyhow::Result;
+use criterion::{BenchmarkId, Criterion};
+use turbo_tasks::{CompletionVc, NothingVc, TryJoinIterExt, TurboTasks};
+use turbo_tasks_memory::MemoryBackend;
+
+use super::register;
+
+pub fn fan_in(c: &mut Criterion) {
+    if matches!(
+        std::env::var("TURBOPACK_BENCH_STRESS").ok().as_deref(),
+        None | Some("") | Some("no") | Some("false")
+    ) {
+        return;
+    }
+
+    register();
+
+    let mut group = c.benchmark_group("turbo_tasks_memory_fan_in");
+    group.sample_size(20);
+
+    for size in [100, 1000, 10000] {
+        group.throughput(criterion::Throughput::Elements(
+            /* readers + shared task + root task = */
+            size as u64 + 2,
+        ));
+        group.bench_with_input(BenchmarkId::new("shared", size), &size, |b, size| {
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let size = *size;
+
+            b.to_async(rt).iter_with_large_drop(move || {
+                let tt = TurboTasks::new(MemoryBackend::new());
+                async move {
+                    let task = tt.spawn_once_task(async move {
+                        (0..size).map(reader).try_join().await?;
+                        Ok(NothingVc::new().into())
+                    });
+                    tt.wait_task_completion(task, false).await.unwrap();
+                    tt
+                }
+            })
+        });
+    }
+}
+
+/// A task that is read by every reader, like a shared config.
+#[turbo_tasks::function]
+fn shared() -> CompletionVc {
+    CompletionVc::new()
+}
+
+/// Calls the shared task multiple times, so all but the first call hit the
+/// already existing edge.
+#[turbo_tasks::function]
+async fn reader(_key: u32) -> Result<CompletionVc> {
+    for _ in 0..10 {
+        shared().await?;
+    }
+    Ok(CompletionVc::new())
+}
@@ -0,0 +1,34 @@
+use serde::Serialize;
+use turbo_tasks::{registry, SharedReference, SharedValue, TaskInput};
+
+/// A cached trait call and the functions it has been dispatched to.
+#[derive(Serialize, Debug)]
+pub struct TraitCallResolution {
+    /// The task that resolves the trait call.
+    pub task: usize,
+    /// Summaries of the call inputs.
+    pub inputs: Vec<String>,
+    /// The implementations that have been called for these inputs.
+    pub implementations: Vec<String>,
+}
+
+pub(crate) fn summarize_input(input: &TaskInput) -> String {
+    match input {
+        TaskInput::TaskOutput(task) => format!("output of {}", task),
+        TaskInput::TaskCell(task, index) => format!("cell {} of {}", index, task),
+        TaskInput::List(list) => format!("list of {} items", list.len()),
+        TaskInput::String(_) => "string".to_string(),
+        TaskInput::Bool(_) => "bool".to_string(),
+        TaskInput::Usize(_) => "usize".to_string(),
+        TaskInput::I32(_) => "i32".to_string(),
+        TaskInput::U32(_) => "u32".to_string(),
+        TaskInput::U64(_) => "u64".to_string(),
+        TaskInput::Nothing => "nothing".to_string(),
+        TaskInput::SharedValue(SharedValue(ty, _))
+        | TaskInput::SharedReference(SharedReference(ty, _)) => match ty {
+            Some(ty) => registry::get_value_type(*ty).name.clone(),
+            None => "untyped value".to_string(),
+        },
+        TaskInput::TransientSharedValue(_) => "transient value".to_string(),
+    }
+}
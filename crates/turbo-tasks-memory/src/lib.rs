@@ -4,6 +4,7 @@
 
 mod cell;
 mod count_hash_set;
+pub mod export;
 mod memory_backend;
 mod memory_backend_with_pg;
 mod output;
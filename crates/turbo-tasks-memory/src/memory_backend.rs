@@ -4,31 +4,36 @@ use std::{
     collections::{HashSet, VecDeque},
     future::Future,
     hash::BuildHasherDefault,
+    mem::size_of,
     pin::Pin,
     sync::atomic::{AtomicUsize, Ordering},
     time::Duration,
 };
 
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 use dashmap::{mapref::entry::Entry, DashMap};
 use event_listener::EventListener;
 use rustc_hash::FxHasher;
 use tokio::task::futures::TaskLocalFuture;
 use turbo_tasks::{
     backend::{
-        Backend, BackendJobId, CellContent, CellMappings, PersistentTaskType, TaskExecutionSpec,
-        TransientTaskType,
+        Backend, BackendJobId, CellContent, CellMappings, GraphExport, GraphSize,
+        PersistentTaskType, TaskExecutionSpec, TransientTaskType,
     },
+    registry,
     util::{IdFactory, NoMoveVec},
     RawVc, TaskId, TraitTypeId, TurboTasksBackendApi,
 };
 
 use crate::{
+    cell::Cell,
+    export::{summarize_input, TraitCallResolution},
     output::Output,
     scope::{TaskScope, TaskScopeId},
+    stats,
     task::{
-        run_add_to_scope_queue, run_remove_from_scope_queue, Task, TaskDependency,
-        DEPENDENCIES_TO_TRACK,
+        run_add_to_scope_queue, run_remove_from_scope_queue, NativeTaskFuture, Task,
+        TaskDependency, DEPENDENCIES_TO_TRACK,
     },
 };
 
@@ -41,8 +46,16 @@ pub struct MemoryBackend {
     backend_job_id_factory: IdFactory<BackendJobId>,
     task_cache: DashMap<PersistentTaskType, TaskId, BuildHasherDefault<FxHasher>>,
     scope_generation: AtomicUsize,
+    max_task_depth: AtomicUsize,
+    task_count: AtomicUsize,
+    cell_count: AtomicUsize,
+    redundant_schedules: AtomicUsize,
 }
 
+/// The default for [MemoryBackend::set_max_task_depth]. Legitimate task graphs
+/// stay far below that.
+const DEFAULT_MAX_TASK_DEPTH: usize = 100_000;
+
 impl Default for MemoryBackend {
     fn default() -> Self {
         Self::new()
@@ -66,9 +79,20 @@ impl MemoryBackend {
             backend_job_id_factory: IdFactory::new(),
             task_cache: DashMap::default(),
             scope_generation: AtomicUsize::new(0),
+            max_task_depth: AtomicUsize::new(DEFAULT_MAX_TASK_DEPTH),
+            task_count: AtomicUsize::new(0),
+            cell_count: AtomicUsize::new(0),
+            redundant_schedules: AtomicUsize::new(0),
         }
     }
 
+    /// Sets the maximum length of the parent chain of a task. Tasks beyond
+    /// that depth fail instead of being executed, so a recursion without a
+    /// base case results in an error instead of exhausting memory.
+    pub fn set_max_task_depth(&self, depth: usize) {
+        self.max_task_depth.store(depth, Ordering::Relaxed);
+    }
+
     fn connect_task_child(
         &self,
         parent: TaskId,
@@ -107,6 +131,66 @@ impl MemoryBackend {
         }
     }
 
+    /// Like [MemoryBackend::with_all_cached_tasks], but only calls `func` for
+    /// tasks that are scheduled and haven't started executing yet. This helps
+    /// to find out why the queue isn't draining.
+    pub fn with_scheduled_tasks(&self, mut func: impl FnMut(TaskId)) {
+        self.with_all_cached_tasks(|id| {
+            if self.with_task(id, |task| task.is_scheduled()) {
+                func(id);
+            }
+        });
+    }
+
+    /// Returns how often a scheduled task couldn't be started since it was
+    /// already in progress or done. This should stay close to 0, otherwise
+    /// tasks are scheduled more often than needed.
+    pub fn redundant_schedules(&self) -> usize {
+        self.redundant_schedules.load(Ordering::Relaxed)
+    }
+
+    /// Returns how often `task` has been executed since the stats have been
+    /// reset. A task that executes a lot more often than its inputs change
+    /// likely depends on something too granular.
+    pub fn task_executions(&self, task: TaskId) -> u32 {
+        self.with_task(task, |task| task.executions())
+    }
+
+    /// Lists the cached calls of the trait function `trait_fn_name` of
+    /// `trait_type` together with the implementations they have been
+    /// dispatched to. This helps to find out why a trait call picked a
+    /// certain implementation.
+    pub fn trait_call_resolutions(
+        &self,
+        trait_type: TraitTypeId,
+        trait_fn_name: &str,
+    ) -> Vec<TraitCallResolution> {
+        self.task_cache
+            .iter()
+            .filter_map(|entry| match entry.key() {
+                PersistentTaskType::ResolveTrait(ty, name, inputs)
+                    if *ty == trait_type && name == trait_fn_name =>
+                {
+                    let id = *entry.value();
+                    let (references, _) = self.with_task(id, |task| task.get_stats_references());
+                    let implementations = references
+                        .into_iter()
+                        .filter(|(ty, _)| matches!(ty, stats::ReferenceType::Child))
+                        .map(|(_, child)| {
+                            self.with_task(child, |child| child.get_stats_type().to_string())
+                        })
+                        .collect();
+                    Some(TraitCallResolution {
+                        task: *id,
+                        inputs: inputs.iter().map(summarize_input).collect(),
+                        implementations,
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn with_task<T>(&self, id: TaskId, func: impl FnOnce(&Task) -> T) -> T {
         func(self.memory_tasks.get(*id).unwrap())
     }
@@ -244,11 +328,24 @@ impl Backend for MemoryBackend {
         self.with_task(task, |task| {
             if task.execution_started(self, turbo_tasks) {
                 let cell_mappings = task.take_cell_mappings();
+                let max_task_depth = self.max_task_depth.load(Ordering::Relaxed);
+                let future: NativeTaskFuture = if task.depth() > max_task_depth {
+                    let description = task.get_description();
+                    Box::pin(async move {
+                        Err::<RawVc, _>(anyhow!(
+                            "{description} exceeds the maximum task depth of {max_task_depth}, \
+                             this is likely caused by a recursion without a base case"
+                        ))
+                    })
+                } else {
+                    task.execute(turbo_tasks)
+                };
                 Some(TaskExecutionSpec {
                     cell_mappings: Some(cell_mappings),
-                    future: task.execute(turbo_tasks),
+                    future,
                 })
             } else {
+                self.redundant_schedules.fetch_add(1, Ordering::Relaxed);
                 None
             }
         })
@@ -327,6 +424,8 @@ impl Backend for MemoryBackend {
         reader: TaskId,
         _turbo_tasks: &dyn TurboTasksBackendApi,
     ) -> Result<Result<CellContent, EventListener>> {
+        #[cfg(feature = "track_cell_stats")]
+        self.with_task(reader, |reader| reader.record_cell_read());
         if task == reader {
             Ok(Ok(self.with_task(task, |task| {
                 task.with_cell_mut(index, |cell| cell.read_content_untracked())
@@ -402,6 +501,7 @@ impl Backend for MemoryBackend {
     }
 
     fn get_fresh_cell(&self, task: TaskId, _turbo_tasks: &dyn TurboTasksBackendApi) -> usize {
+        self.cell_count.fetch_add(1, Ordering::Relaxed);
         self.with_task(task, |task| task.get_fresh_cell())
     }
 
@@ -413,6 +513,8 @@ impl Backend for MemoryBackend {
         turbo_tasks: &dyn TurboTasksBackendApi,
     ) {
         self.with_task(task, |task| {
+            #[cfg(feature = "track_cell_stats")]
+            task.record_cell_write(index, &content);
             task.with_cell_mut(index, |cell| cell.assign(content, turbo_tasks))
         })
     }
@@ -445,6 +547,7 @@ impl Backend for MemoryBackend {
     ) -> TaskId {
         let result = if let Some(task) = self.task_cache.get(&task_type).map(|task| *task) {
             // fast pass without creating a new task
+            record_cache_hit(&task_type);
             self.connect_task_child(parent_task, task, turbo_tasks);
 
             // TODO maybe force (background) scheduling to avoid inactive tasks hanging in
@@ -453,35 +556,46 @@ impl Backend for MemoryBackend {
         } else {
             // slow pass with key lock
             let id = turbo_tasks.get_fresh_task_id();
+            let depth = self.with_task(parent_task, |parent| parent.depth()) + 1;
             let task = match &task_type {
                 PersistentTaskType::Native(fn_id, inputs) => {
                     // TODO inputs doesn't need to be cloned when are would be able to get a
                     // reference to the task type stored inside of the task
-                    Task::new_native(id, inputs.clone(), *fn_id)
+                    Task::new_native(id, inputs.clone(), *fn_id, depth)
                 }
                 PersistentTaskType::ResolveNative(fn_id, inputs) => {
-                    Task::new_resolve_native(id, inputs.clone(), *fn_id)
+                    Task::new_resolve_native(id, inputs.clone(), *fn_id, depth)
                 }
                 PersistentTaskType::ResolveTrait(trait_type, trait_fn_name, inputs) => {
-                    Task::new_resolve_trait(id, *trait_type, trait_fn_name.clone(), inputs.clone())
+                    Task::new_resolve_trait(
+                        id,
+                        *trait_type,
+                        trait_fn_name.clone(),
+                        inputs.clone(),
+                        depth,
+                    )
                 }
             };
             // Safety: We have a fresh task id that nobody knows about yet
             unsafe {
                 self.memory_tasks.insert(*id, task);
             }
+            self.task_count.fetch_add(1, Ordering::Relaxed);
             let result_task = match self.task_cache.entry(task_type) {
                 Entry::Vacant(entry) => {
                     // This is the most likely case
+                    record_cache_miss(entry.key());
                     entry.insert(id);
                     id
                 }
                 Entry::Occupied(entry) => {
+                    record_cache_hit(entry.key());
                     // Safety: We have a fresh task id that nobody knows about yet
                     unsafe {
                         self.memory_tasks.remove(*id);
                         turbo_tasks.reuse_task_id(id);
                     }
+                    self.task_count.fetch_sub(1, Ordering::Relaxed);
                     *entry.get()
                 }
             };
@@ -510,10 +624,29 @@ impl Backend for MemoryBackend {
         // SAFETY: We have a fresh task id where nobody knows about yet
         #[allow(unused_variables)]
         let task = unsafe { self.memory_tasks.insert(*id, task) };
+        self.task_count.fetch_add(1, Ordering::Relaxed);
         #[cfg(feature = "print_scope_updates")]
         println!("new {scope} for {task}");
         id
     }
+
+    fn graph_size(&self) -> GraphSize {
+        let tasks = self.task_count.load(Ordering::Relaxed);
+        let cells = self.cell_count.load(Ordering::Relaxed);
+        GraphSize {
+            tasks,
+            cells,
+            estimated_bytes: tasks * size_of::<Task>() + cells * size_of::<Cell>(),
+        }
+    }
+
+    fn export_graph(&self) -> GraphExport {
+        let mut tasks = Vec::new();
+        self.with_all_cached_tasks(|id| {
+            self.with_task(id, |task| tasks.push(task.get_export_node()));
+        });
+        GraphExport { tasks }
+    }
 }
 
 pub(crate) enum Job {
@@ -561,3 +694,15 @@ impl Job {
         }
     }
 }
+
+fn record_cache_hit(task_type: &PersistentTaskType) {
+    if let PersistentTaskType::Native(fn_id, _) = task_type {
+        registry::get_function(*fn_id).record_cache_hit();
+    }
+}
+
+fn record_cache_miss(task_type: &PersistentTaskType) {
+    if let PersistentTaskType::Native(fn_id, _) = task_type {
+        registry::get_function(*fn_id).record_cache_miss();
+    }
+}
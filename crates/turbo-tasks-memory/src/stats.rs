@@ -55,6 +55,13 @@ pub struct TaskStats {
     pub count: usize,
     pub active_count: usize,
     pub executions: usize,
+    /// Only tracked with the `track_cell_stats` feature
+    pub cell_reads: usize,
+    /// Only tracked with the `track_cell_stats` feature
+    pub cell_writes: usize,
+    /// The estimated memory held by the cells of the tasks. Only tracked with
+    /// the `track_cell_stats` feature
+    pub cell_bytes: usize,
     pub roots: usize,
     pub scopes: usize,
     pub total_duration: Duration,
@@ -70,6 +77,9 @@ impl Default for TaskStats {
             count: 0,
             active_count: 0,
             executions: 0,
+            cell_reads: 0,
+            cell_writes: 0,
+            cell_bytes: 0,
             roots: 0,
             scopes: 0,
             total_duration: Duration::ZERO,
@@ -117,6 +127,9 @@ impl Stats {
             total_duration,
             last_duration,
             executions,
+            cell_reads,
+            cell_writes,
+            cell_bytes,
             root_scoped,
             child_scopes,
             active,
@@ -133,6 +146,9 @@ impl Stats {
         }
         stats.max_duration = max(stats.max_duration, last_duration);
         stats.executions += executions as usize;
+        stats.cell_reads += cell_reads;
+        stats.cell_writes += cell_writes;
+        stats.cell_bytes += cell_bytes;
         if root_scoped {
             stats.roots += 1;
         }
@@ -149,6 +165,11 @@ impl Stats {
         }
     }
 
+    /// Iterates the collected stats per type of task.
+    pub fn iter(&self) -> impl Iterator<Item = (&TaskType, &TaskStats)> {
+        self.tasks.iter()
+    }
+
     pub fn add_id(&mut self, backend: &MemoryBackend, id: TaskId) {
         backend.with_task(id, |task| {
             self.add(backend, task);
@@ -17,7 +17,7 @@ use event_listener::{Event, EventListener};
 use parking_lot::{RwLock, RwLockWriteGuard};
 use tokio::task_local;
 use turbo_tasks::{
-    backend::{CellMappings, PersistentTaskType},
+    backend::{CellMappings, PersistentTaskType, TaskNode},
     get_invalidator, registry, FunctionId, Invalidator, RawVc, TaskId, TaskInput, TraitTypeId,
     TurboTasksBackendApi,
 };
@@ -110,6 +110,9 @@ pub struct Task {
     inputs: Vec<TaskInput>,
     /// The type of the task
     ty: TaskType,
+    /// The length of the parent chain from a root task to the task that
+    /// created this task.
+    depth: usize,
     /// The mutable state of the task
     state: RwLock<TaskState>,
     // TODO technically we need no lock here as it's only written
@@ -171,6 +174,14 @@ struct TaskState {
     executions: u32,
     total_duration: Duration,
     last_duration: Duration,
+    /// Only tracked with the `track_cell_stats` feature
+    cell_reads: usize,
+    /// Only tracked with the `track_cell_stats` feature
+    cell_writes: usize,
+    /// The estimated memory held by the cells of this task, see
+    /// [turbo_tasks::MemorySize]. Only tracked with the `track_cell_stats`
+    /// feature
+    cell_bytes: usize,
 }
 
 /// Keeps track of emitted and unemitted collectibles. Defaults to None to avoid
@@ -261,6 +272,7 @@ use TaskStateType::*;
 use crate::{
     cell::Cell,
     count_hash_set::CountHashSet,
+    export,
     memory_backend::Job,
     output::Output,
     scope::{ScopeChildChangeEffect, TaskScopeId, TaskScopes},
@@ -268,12 +280,18 @@ use crate::{
 };
 
 impl Task {
-    pub(crate) fn new_native(id: TaskId, inputs: Vec<TaskInput>, native_fn: FunctionId) -> Self {
+    pub(crate) fn new_native(
+        id: TaskId,
+        inputs: Vec<TaskInput>,
+        native_fn: FunctionId,
+        depth: usize,
+    ) -> Self {
         let bound_fn = registry::get_function(native_fn).bind(&inputs);
         Self {
             id,
             inputs,
             ty: TaskType::Native(native_fn, bound_fn),
+            depth,
             state: Default::default(),
             execution_data: Default::default(),
         }
@@ -283,11 +301,13 @@ impl Task {
         id: TaskId,
         inputs: Vec<TaskInput>,
         native_fn: FunctionId,
+        depth: usize,
     ) -> Self {
         Self {
             id,
             inputs,
             ty: TaskType::ResolveNative(native_fn),
+            depth,
             state: Default::default(),
             execution_data: Default::default(),
         }
@@ -298,11 +318,13 @@ impl Task {
         trait_type: TraitTypeId,
         trait_fn_name: Cow<'static, str>,
         inputs: Vec<TaskInput>,
+        depth: usize,
     ) -> Self {
         Self {
             id,
             inputs,
             ty: TaskType::ResolveTrait(trait_type, trait_fn_name),
+            depth,
             state: Default::default(),
             execution_data: Default::default(),
         }
@@ -317,6 +339,7 @@ impl Task {
             id,
             inputs: Vec::new(),
             ty: TaskType::Root(Box::new(functor)),
+            depth: 0,
             state: RwLock::new(TaskState {
                 state_type: Scheduled,
                 scopes: TaskScopes::Inner(CountHashSet::from([scope]), 0),
@@ -335,6 +358,7 @@ impl Task {
             id,
             inputs: Vec::new(),
             ty: TaskType::Once(Mutex::new(Some(Box::pin(functor)))),
+            depth: 0,
             state: RwLock::new(TaskState {
                 state_type: Scheduled,
                 scopes: TaskScopes::Inner(CountHashSet::from([scope]), 0),
@@ -344,6 +368,10 @@ impl Task {
         }
     }
 
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
     pub(crate) fn get_description(&self) -> String {
         match &self.ty {
             TaskType::Root(..) => format!("[{}] root", self.id),
@@ -1132,6 +1160,38 @@ impl Task {
         func(&state.created_cells[index])
     }
 
+    /// Counts a cell read performed by this task.
+    #[cfg(feature = "track_cell_stats")]
+    pub(crate) fn record_cell_read(&self) {
+        self.state.write().cell_reads += 1;
+    }
+
+    /// Counts a cell write performed by this task and updates the memory held
+    /// by its cells.
+    #[cfg(feature = "track_cell_stats")]
+    pub(crate) fn record_cell_write(
+        &self,
+        index: usize,
+        content: &turbo_tasks::backend::CellContent,
+    ) {
+        let mut state = self.state.write();
+        let previous = state.created_cells[index]
+            .read_content_untracked()
+            .memory_size();
+        // Sizes are estimates and the previous content isn't guaranteed to report
+        // the same size as when it was written, so the total must not underflow
+        state.cell_bytes = state
+            .cell_bytes
+            .saturating_sub(previous)
+            .saturating_add(content.memory_size());
+        state.cell_writes += 1;
+    }
+
+    /// How often the task has been executed since the stats have been reset.
+    pub(crate) fn executions(&self) -> u32 {
+        self.state.read().executions
+    }
+
     /// For testing purposes
     pub fn reset_executions(&self) {
         let mut state = self.state.write();
@@ -1145,11 +1205,19 @@ impl Task {
         state.state_type != TaskStateType::Done
     }
 
+    /// Whether the task is scheduled, but its execution hasn't started yet.
+    pub fn is_scheduled(&self) -> bool {
+        let state = self.state.read();
+        state.state_type == TaskStateType::Scheduled
+    }
+
     pub fn reset_stats(&self) {
         let mut state = self.state.write();
         state.executions = 0;
         state.total_duration = Duration::ZERO;
         state.last_duration = Duration::ZERO;
+        state.cell_reads = 0;
+        state.cell_writes = 0;
     }
 
     pub fn get_stats_info(&self, backend: &MemoryBackend) -> TaskStatsInfo {
@@ -1158,6 +1226,9 @@ impl Task {
             total_duration: state.total_duration,
             last_duration: state.last_duration,
             executions: state.executions,
+            cell_reads: state.cell_reads,
+            cell_writes: state.cell_writes,
+            cell_bytes: state.cell_bytes,
             root_scoped: matches!(state.scopes, TaskScopes::Root(_)),
             child_scopes: match state.scopes {
                 TaskScopes::Root(_) => 1,
@@ -1218,6 +1289,30 @@ impl Task {
         (refs, scope_refs)
     }
 
+    pub(crate) fn get_export_node(&self) -> TaskNode {
+        let (references, _) = self.get_stats_references();
+        let mut children = Vec::new();
+        let mut dependencies = Vec::new();
+        for (ty, task) in references {
+            match ty {
+                stats::ReferenceType::Child => children.push(*task),
+                stats::ReferenceType::Dependency => dependencies.push(*task),
+                stats::ReferenceType::Input => {}
+            }
+        }
+        // A task might read multiple cells of the same task
+        dependencies.sort_unstable();
+        dependencies.dedup();
+        TaskNode {
+            id: *self.id,
+            function: self.get_stats_type().to_string(),
+            inputs: self.inputs.iter().map(export::summarize_input).collect(),
+            children,
+            dependencies,
+            executions: self.executions(),
+        }
+    }
+
     fn state_string(state: &TaskState) -> String {
         let mut state_str = match state.state_type {
             Scheduled => "scheduled".to_string(),
@@ -1252,6 +1347,11 @@ impl Task {
         backend: &MemoryBackend,
         turbo_tasks: &dyn TurboTasksBackendApi,
     ) {
+        // Cached calls reconnect to the same child over and over, so check
+        // for an existing edge under the read lock first
+        if self.state.read().children.contains(&child_id) {
+            return;
+        }
         let mut state = self.state.write();
         if state.children.insert(child_id) {
             let scopes = state.scopes.clone();
@@ -1537,6 +1637,9 @@ pub struct TaskStatsInfo {
     pub total_duration: Duration,
     pub last_duration: Duration,
     pub executions: u32,
+    pub cell_reads: usize,
+    pub cell_writes: usize,
+    pub cell_bytes: usize,
     pub root_scoped: bool,
     pub child_scopes: usize,
     pub active: bool,
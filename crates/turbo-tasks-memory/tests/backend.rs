@@ -0,0 +1,244 @@
+#![feature(min_specialization)]
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use turbo_tasks::{
+    RawVc, TaskId, TaskObserver, TaskTransition, TryJoinIterExt, TurboTasks, TurboTasksBackendApi,
+};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::register;
+
+register!();
+
+#[tokio::test]
+async fn scheduling_a_done_task_is_counted() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    let task = tt
+        .run_once(async {
+            let value = number(42);
+            value.strongly_consistent().await?;
+            Ok(*task_of(value.into()))
+        })
+        .await
+        .unwrap();
+    let task = task.into();
+    let before = tt.backend().redundant_schedules();
+
+    TurboTasksBackendApi::schedule(&*tt, task);
+    while tt.backend().redundant_schedules() == before {
+        tokio::task::yield_now().await;
+    }
+
+    assert_eq!(tt.backend().redundant_schedules(), before + 1);
+    assert_eq!(tt.backend().task_executions(task), 1);
+}
+
+#[tokio::test]
+async fn lists_tasks_that_have_not_started() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    let inner_tt = tt.clone();
+    tt.run_once(async move {
+        let scheduled = || {
+            let mut tasks = Vec::new();
+            inner_tt.backend().with_scheduled_tasks(|id| tasks.push(id));
+            tasks
+        };
+
+        // The test runtime is single threaded, so the called tasks can't start
+        // before this task yields
+        let first = number(1);
+        let second = number(2);
+        let tasks = scheduled();
+        assert!(tasks.contains(&task_of(first.into())));
+        assert!(tasks.contains(&task_of(second.into())));
+
+        assert_eq!(*first.await?, 1);
+        assert_eq!(*second.await?, 2);
+        let tasks = scheduled();
+        assert!(!tasks.contains(&task_of(first.into())));
+        assert!(!tasks.contains(&task_of(second.into())));
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[derive(Default)]
+struct RecordingObserver {
+    transitions: Mutex<Vec<(TaskId, TaskTransition)>>,
+}
+
+impl TaskObserver for RecordingObserver {
+    fn on_transition(&self, task: TaskId, transition: TaskTransition) {
+        self.transitions.lock().unwrap().push((task, transition));
+    }
+}
+
+#[tokio::test]
+async fn observe_task_transitions() {
+    *REGISTER;
+    let observer = Arc::new(RecordingObserver::default());
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.set_task_observer(Some(observer.clone()));
+    let task = tt
+        .run_once(async {
+            let output = number(42);
+            assert_eq!(*output.await?, 42);
+            Ok(*task_of(output.into()))
+        })
+        .await
+        .unwrap();
+
+    let transitions = observer
+        .transitions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(id, _)| **id == task)
+        .map(|(_, transition)| *transition)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        transitions,
+        [
+            TaskTransition::Scheduled,
+            TaskTransition::ExecutionStarted,
+            TaskTransition::Completed
+        ]
+    );
+}
+
+#[tokio::test]
+async fn graph_size_grows_with_tasks() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    let before = tt.graph_size();
+    tt.run_once(async {
+        assert_eq!(*number(42).await?, 42);
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let after = tt.graph_size();
+    // the once task and the `number` task
+    assert_eq!(after.tasks, before.tasks + 2);
+    assert!(after.cells > before.cells);
+    assert!(after.estimated_bytes > before.estimated_bytes);
+}
+
+#[tokio::test]
+async fn export_graph_lists_tasks_and_edges() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.run_once(async {
+        assert_eq!(*labeled_number().await?, 33);
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let graph = tt.export_graph();
+    let node = |function| {
+        graph
+            .tasks
+            .iter()
+            .find(|task| task.function == function)
+            .unwrap()
+    };
+    let number = node("number");
+    let labeled = node("labeled");
+    let labeled_number = node("labeled_number");
+    assert_eq!(number.inputs, ["u32"]);
+    assert!(number.dependencies.is_empty());
+    assert!(labeled_number.children.contains(&number.id));
+    assert!(labeled_number.children.contains(&labeled.id));
+    assert_eq!(labeled.dependencies, [number.id]);
+    assert_eq!(labeled.executions, 1);
+
+    // Input summaries never contain values
+    assert_eq!(labeled.inputs.len(), 2);
+    assert_eq!(labeled.inputs[0], "string");
+    let json = serde_json::to_string(&graph).unwrap();
+    assert!(!json.contains("secret label"));
+}
+
+#[tokio::test]
+async fn queue_depth_samples_are_bounded() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    assert!(tt.queue_depth_samples().is_empty());
+
+    tt.set_queue_sample_capacity(8);
+    tt.run_once(async {
+        (0..20)
+            .map(|i| number(i).strongly_consistent())
+            .try_join()
+            .await?;
+        Ok(())
+    })
+    .await
+    .unwrap();
+    // The once task finishes its job after its result has been sent
+    while !tt.is_idle() {
+        tokio::task::yield_now().await;
+    }
+
+    let samples = tt.queue_depth_samples();
+    assert_eq!(samples.len(), 8);
+    assert!(samples.windows(2).all(|w| w[0].0 <= w[1].0));
+    // The burst runs multiple tasks at once and the queue drains afterwards
+    assert!(samples.iter().any(|(_, in_progress)| *in_progress > 1));
+    assert_eq!(samples.last().unwrap().1, 0);
+}
+
+#[tokio::test]
+async fn unbounded_recursion_hits_depth_limit() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.backend().set_max_task_depth(100);
+    let result = tt
+        .run_once(async {
+            recurse(0).await?;
+            Ok(())
+        })
+        .await;
+
+    let message = format!("{:#}", result.unwrap_err());
+    assert!(message.contains("exceeds the maximum task depth of 100"));
+    assert!(message.contains("recurse"));
+}
+
+fn task_of(vc: RawVc) -> TaskId {
+    match vc {
+        RawVc::TaskOutput(task) | RawVc::TaskCell(task, _) => task,
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::function]
+fn number(value: u32) -> NumberVc {
+    NumberVc::cell(value)
+}
+
+#[turbo_tasks::function]
+async fn recurse(n: u32) -> Result<NumberVc> {
+    // No base case
+    Ok(NumberVc::cell(*recurse(n + 1).await? + 1))
+}
+
+#[turbo_tasks::function]
+async fn labeled(label: String, value: NumberVc) -> Result<NumberVc> {
+    Ok(NumberVc::cell(*value.await? + label.len() as u32))
+}
+
+#[turbo_tasks::function]
+async fn labeled_number() -> Result<NumberVc> {
+    // Resolving the number first avoids a separate resolve task
+    let number = number(21).resolve().await?;
+    Ok(labeled("secret label".to_string(), number))
+}
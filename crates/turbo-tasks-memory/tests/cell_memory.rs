@@ -0,0 +1,95 @@
+#![cfg(feature = "track_cell_stats")]
+#![feature(min_specialization)]
+
+use std::mem::size_of;
+
+use anyhow::Result;
+use turbo_tasks::{MemorySize, TurboTasks};
+use turbo_tasks_memory::{
+    stats::{Stats, TaskStats},
+    MemoryBackend,
+};
+use turbo_tasks_testing::register;
+
+register!();
+
+#[tokio::test]
+async fn cell_memory_is_attributed_to_the_function() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.run_once(async {
+        big_buffer().await?;
+        small_number().await?;
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    assert!(function_stats(&tt, "big_buffer").cell_bytes >= 1_000_000);
+    assert!(function_stats(&tt, "small_number").cell_bytes < 1_000);
+}
+
+#[tokio::test]
+async fn cell_reads_and_writes_are_counted_per_task() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.run_once(async {
+        let a = small_number();
+        assert_eq!(*sum(a, a).await?, 84);
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    // Reads are attributed to the reading task, writes to the task owning the
+    // cell
+    let number_stats = function_stats(&tt, "small_number");
+    assert_eq!(number_stats.cell_reads, 0);
+    assert_eq!(number_stats.cell_writes, 1);
+    let sum_stats = function_stats(&tt, "sum");
+    assert_eq!(sum_stats.cell_reads, 2);
+    assert_eq!(sum_stats.cell_writes, 1);
+}
+
+fn function_stats(tt: &TurboTasks<MemoryBackend>, function: &str) -> TaskStats {
+    let backend = tt.backend();
+    let mut stats = Stats::new();
+    backend.with_all_cached_tasks(|id| stats.add_id(backend, id));
+    stats
+        .iter()
+        .find(|(ty, _)| ty.to_string() == function)
+        .map(|(_, stats)| stats.clone())
+        .unwrap()
+}
+
+#[turbo_tasks::value]
+struct Buffer {
+    data: Vec<u8>,
+}
+
+impl MemorySize for Buffer {
+    fn memory_size(&self) -> usize {
+        size_of::<Self>() + self.data.capacity()
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::function]
+fn big_buffer() -> BufferVc {
+    Buffer {
+        data: vec![0; 1_000_000],
+    }
+    .cell()
+}
+
+#[turbo_tasks::function]
+fn small_number() -> NumberVc {
+    NumberVc::cell(42)
+}
+
+#[turbo_tasks::function]
+async fn sum(a: NumberVc, b: NumberVc) -> Result<NumberVc> {
+    Ok(NumberVc::cell(*a.await? + *b.await?))
+}
@@ -0,0 +1,221 @@
+#![feature(min_specialization)]
+
+use std::sync::Mutex;
+
+use anyhow::{bail, Result};
+use turbo_tasks::{get_invalidator, Invalidator, RawVc, TaskId, TurboTasks};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+static SEEN: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+#[tokio::test]
+async fn get_or_default_on_error() {
+    run! {
+        let source = new_source(0);
+        let value = non_zero_or_default(source.get_value());
+        assert_eq!(*value.strongly_consistent().await?, 42);
+
+        source.await?.change(7);
+        assert_eq!(*value.strongly_consistent().await?, 7);
+    }
+}
+
+#[tokio::test]
+async fn inspect_observes_values() {
+    run! {
+        let source = new_source(0);
+        let doubled = doubled(source);
+        assert_eq!(*doubled.strongly_consistent().await?, 0);
+
+        source.await?.change(1);
+        assert_eq!(*doubled.strongly_consistent().await?, 2);
+        assert_eq!(*SEEN.lock().unwrap(), [0, 1]);
+    }
+}
+
+#[tokio::test]
+async fn keyed_cells_are_stable_across_branch_orders() {
+    run! {
+        let source = new_source(0);
+        let before = numbers(source).strongly_consistent().await?;
+        assert_eq!(*before.a.await?, 1);
+        assert_eq!(*before.b.await?, 2);
+
+        source.await?.change(1);
+        let after = numbers(source).strongly_consistent().await?;
+        assert_eq!(RawVc::from(before.a), RawVc::from(after.a));
+        assert_eq!(RawVc::from(before.b), RawVc::from(after.b));
+        assert_eq!(*after.a.await?, 1);
+        assert_eq!(*after.b.await?, 2);
+    }
+}
+
+#[tokio::test]
+async fn executions_are_counted_per_recomputation() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    let (source, task) = tt
+        .run_once(async {
+            let source = new_source(0);
+            let value = source.get_value();
+            value.strongly_consistent().await?;
+            Ok((source, *task_of(value.into())))
+        })
+        .await
+        .unwrap();
+    let task = task.into();
+    assert_eq!(tt.backend().task_executions(task), 1);
+
+    for expected in 2..=3 {
+        tt.run_once(async move {
+            source.await?.change(expected);
+            source.get_value().strongly_consistent().await?;
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert_eq!(tt.backend().task_executions(task), expected);
+    }
+}
+
+#[tokio::test]
+async fn diff_lists_reexecuted_tasks() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    let source = tt
+        .run_once(async {
+            let source = new_source(1);
+            assert_eq!(*double(source.get_value()).strongly_consistent().await?, 2);
+            assert_eq!(*answer().strongly_consistent().await?, 42);
+            Ok(source)
+        })
+        .await
+        .unwrap();
+    let before = tt.export_graph();
+
+    tt.run_once(async move {
+        source.await?.change(2);
+        assert_eq!(*double(source.get_value()).strongly_consistent().await?, 4);
+        assert_eq!(*answer().strongly_consistent().await?, 42);
+        Ok(())
+    })
+    .await
+    .unwrap();
+    let diff = tt.export_graph().diff(&before);
+
+    let reexecuted = diff
+        .reexecuted
+        .iter()
+        .map(|task| task.function.as_str())
+        .collect::<Vec<_>>();
+    assert!(reexecuted.iter().any(|f| f.ends_with("::get_value")));
+    assert!(reexecuted.contains(&"double"));
+    assert!(!reexecuted.contains(&"answer"));
+    assert!(diff.added.is_empty());
+    assert!(diff.unchanged > 0);
+}
+
+fn task_of(vc: RawVc) -> TaskId {
+    match vc {
+        RawVc::TaskOutput(task) | RawVc::TaskCell(task, _) => task,
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct Value(u32);
+
+#[turbo_tasks::value(serialization = "none", cell = "new", eq = "manual")]
+struct Source {
+    /// The current value, and the invalidator for the task reading it
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    state: Mutex<(u32, Option<Invalidator>)>,
+}
+
+fn new_source(value: u32) -> SourceVc {
+    SourceVc::cell(Source {
+        state: Mutex::new((value, None)),
+    })
+}
+
+impl Source {
+    fn change(&self, value: u32) {
+        let mut state = self.state.lock().unwrap();
+        state.0 = value;
+        if let Some(invalidator) = state.1.take() {
+            invalidator.invalidate();
+        }
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl SourceVc {
+    #[turbo_tasks::function]
+    pub async fn get_value(self) -> Result<ValueVc> {
+        let this = self.await?;
+        let mut state = this.state.lock().unwrap();
+        state.1 = Some(get_invalidator());
+        Ok(ValueVc::cell(state.0))
+    }
+}
+
+#[turbo_tasks::function]
+async fn double(value: ValueVc) -> Result<ValueVc> {
+    Ok(ValueVc::cell(*value.await? * 2))
+}
+
+#[turbo_tasks::function]
+fn answer() -> ValueVc {
+    ValueVc::cell(42)
+}
+
+#[turbo_tasks::function]
+async fn non_zero(value: ValueVc) -> Result<ValueVc> {
+    if *value.await? == 0 {
+        bail!("value is zero");
+    }
+    Ok(value)
+}
+
+#[turbo_tasks::function]
+async fn non_zero_or_default(value: ValueVc) -> Result<ValueVc> {
+    Ok(ValueVc::cell(*non_zero(value).get_or(|| 42).await))
+}
+
+#[turbo_tasks::function]
+async fn doubled(source: SourceVc) -> Result<ValueVc> {
+    let value = source
+        .get_value()
+        .inspect(|value| SEEN.lock().unwrap().push(*value))
+        .await?;
+    Ok(ValueVc::cell(*value.await? * 2))
+}
+
+#[turbo_tasks::value(serialization = "auto_for_input")]
+#[derive(Debug, PartialOrd, Ord, Hash)]
+struct Key(u32);
+
+#[turbo_tasks::value]
+struct Numbers {
+    a: ValueVc,
+    b: ValueVc,
+}
+
+/// Creates the same keyed cells in reverse order once the value of the source
+/// isn't zero
+#[turbo_tasks::function]
+async fn numbers(source: SourceVc) -> Result<NumbersVc> {
+    let reversed = *source.get_value().await? != 0;
+    let (a, b) = if reversed {
+        let b = ValueVc::keyed_cell(Key(2), 2);
+        let a = ValueVc::keyed_cell(Key(1), 1);
+        (a, b)
+    } else {
+        let a = ValueVc::keyed_cell(Key(1), 1);
+        let b = ValueVc::keyed_cell(Key(2), 2);
+        (a, b)
+    };
+    Ok(Numbers { a, b }.cell())
+}
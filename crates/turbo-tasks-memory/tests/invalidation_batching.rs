@@ -0,0 +1,144 @@
+#![feature(min_specialization)]
+
+use std::{sync::Mutex, time::Duration};
+
+use anyhow::Result;
+use turbo_tasks::{get_invalidator, Invalidator, TurboTasks};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::register;
+
+register!();
+
+#[tokio::test]
+async fn invalidations_without_a_window_are_applied_one_by_one() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    assert_eq!(change_three_times(&tt).await, 4);
+}
+
+#[tokio::test]
+async fn batched_invalidations_cause_a_single_execution() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.set_invalidation_batch_window(Duration::from_millis(500));
+    assert_eq!(change_three_times(&tt).await, 2);
+}
+
+#[tokio::test]
+async fn pending_invalidations_are_work_in_progress() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.set_invalidation_batch_window(Duration::from_millis(50));
+    let source = tt
+        .run_once(async {
+            let source = SourceVc::cell(Source {
+                state: Mutex::new(SourceState::default()),
+            });
+            assert_eq!(*source.get_value().strongly_consistent().await?, 0);
+            Ok(source)
+        })
+        .await
+        .unwrap();
+
+    tt.run_once(async move {
+        source.await?.change();
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    assert!(!tt.is_idle());
+    while !tt.is_idle() {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[tokio::test]
+async fn strongly_consistent_reads_wait_for_pending_invalidations() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.set_invalidation_batch_window(Duration::from_millis(50));
+    tt.run_once(async {
+        let source = SourceVc::cell(Source {
+            state: Mutex::new(SourceState::default()),
+        });
+        let value = source.get_value();
+        assert_eq!(*value.strongly_consistent().await?, 0);
+
+        source.await?.change();
+        // The invalidation is still pending here
+        assert_eq!(*value.strongly_consistent().await?, 1);
+        assert_eq!(source.await?.executions(), 2);
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+/// Changes a source three times with a pause in between, that gives the
+/// reading task the chance to execute again, and returns how often it was
+/// executed in total.
+async fn change_three_times(tt: &TurboTasks<MemoryBackend>) -> usize {
+    tt.run_once(async {
+        let source = SourceVc::cell(Source {
+            state: Mutex::new(SourceState::default()),
+        });
+        let value = source.get_value();
+        assert_eq!(*value.strongly_consistent().await?, 0);
+        assert_eq!(source.await?.executions(), 1);
+
+        for _ in 0..3 {
+            source.await?.change();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        assert_eq!(*value.strongly_consistent().await?, 3);
+        Ok(source.await?.executions())
+    })
+    .await
+    .unwrap()
+}
+
+#[turbo_tasks::value(transparent)]
+struct SourceValue(usize);
+
+#[derive(Default)]
+struct SourceState {
+    value: usize,
+    executions: usize,
+    invalidators: Vec<Invalidator>,
+}
+
+#[turbo_tasks::value(serialization = "none", cell = "new", eq = "manual")]
+struct Source {
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    state: Mutex<SourceState>,
+}
+
+impl Source {
+    fn change(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.value += 1;
+        if let Some(invalidator) = state.invalidators.pop() {
+            invalidator.invalidate();
+        }
+    }
+
+    fn executions(&self) -> usize {
+        self.state.lock().unwrap().executions
+    }
+}
+
+#[turbo_tasks::value_impl]
+impl SourceVc {
+    #[turbo_tasks::function]
+    pub async fn get_value(self) -> Result<SourceValueVc> {
+        let this = self.await?;
+        let mut state = this.state.lock().unwrap();
+        state.executions += 1;
+        // Use a separate invalidator for every change, so each of them reaches
+        // the backend individually
+        state.invalidators = (0..3).map(|_| get_invalidator()).collect();
+        Ok(SourceValueVc::cell(state.value))
+    }
+}
@@ -0,0 +1,52 @@
+#![feature(min_specialization)]
+
+use anyhow::Result;
+use turbo_tasks::turbo_tasks;
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+#[tokio::test]
+async fn read_computed_value_sync() {
+    run! {
+        let tt = turbo_tasks();
+        let local = ValueVc::cell(42);
+        assert_eq!(*local.try_get_sync(&*tt).unwrap(), 42);
+
+        let output = double(local);
+        assert_eq!(*output.await?, 84);
+        assert_eq!(*output.try_get_sync(&*tt).unwrap(), 84);
+
+        // The task hasn't been executed yet
+        let pending = double(ValueVc::cell(1));
+        assert!(pending.try_get_sync(&*tt).is_none());
+        assert_eq!(*pending.await?, 2);
+    }
+}
+
+#[tokio::test]
+async fn compares_values_of_distinct_cells() {
+    run! {
+        let a = number(1, 42);
+        let b = number(2, 42);
+        let c = number(3, 7);
+        assert_ne!(a.resolve().await?, b.resolve().await?);
+        assert!(a.value_eq(b).await?);
+        assert!(a.value_eq(a).await?);
+        assert!(!a.value_eq(c).await?);
+    }
+}
+
+#[turbo_tasks::value(transparent)]
+struct Value(u32);
+
+#[turbo_tasks::function]
+async fn double(value: ValueVc) -> Result<ValueVc> {
+    Ok(ValueVc::cell(*value.await? * 2))
+}
+
+/// `_id` only makes the calls distinct, so each value is stored in its own cell
+#[turbo_tasks::function]
+fn number(_id: u32, value: u32) -> ValueVc {
+    ValueVc::cell(value)
+}
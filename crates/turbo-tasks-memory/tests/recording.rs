@@ -0,0 +1,88 @@
+#![feature(min_specialization)]
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use turbo_tasks::{CallRecording, TransientInstance, TurboTasks, Value};
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::register;
+
+register!();
+
+#[tokio::test]
+async fn recorded_session_replays_to_the_same_output() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.start_recording();
+    let expected = tt
+        .run_once(async {
+            let product = product(6, 7).await?;
+            let sum = sum(Value::new(Pair(2, 3))).await?;
+            Ok((*product, *sum))
+        })
+        .await
+        .unwrap();
+    let recording = tt.stop_recording();
+    assert_eq!(recording.calls.len(), 2);
+    assert_eq!(recording.skipped, 0);
+
+    // Replay the recording like it has been loaded from a file
+    let json = serde_json::to_string(&recording).unwrap();
+    let recording: CallRecording = serde_json::from_str(&json).unwrap();
+
+    let tt = TurboTasks::new(MemoryBackend::new());
+    let outputs = tt.replay(recording).await.unwrap();
+    let replayed = tt
+        .run_once(async move {
+            let product = NumberVc::from(outputs[0]).await?;
+            let sum = NumberVc::from(outputs[1]).await?;
+            Ok((*product, *sum))
+        })
+        .await
+        .unwrap();
+    assert_eq!(replayed, expected);
+}
+
+#[tokio::test]
+async fn calls_with_non_serializable_inputs_are_skipped() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.start_recording();
+    tt.run_once(async {
+        let label = TransientInstance::from(Arc::new("answer".to_string()));
+        assert_eq!(*label_length(label).await?, 6);
+        Ok(())
+    })
+    .await
+    .unwrap();
+    let recording = tt.stop_recording();
+    assert!(recording.calls.is_empty());
+    assert_eq!(recording.skipped, 1);
+
+    // The recording is still serializable
+    serde_json::to_string(&recording).unwrap();
+}
+
+#[turbo_tasks::value(transparent)]
+struct Number(u32);
+
+#[turbo_tasks::value(serialization = "auto_for_input")]
+#[derive(Debug, Clone, PartialOrd, Ord, Hash)]
+struct Pair(u32, u32);
+
+#[turbo_tasks::function]
+fn product(a: u32, b: u32) -> NumberVc {
+    NumberVc::cell(a * b)
+}
+
+#[turbo_tasks::function]
+fn sum(pair: Value<Pair>) -> NumberVc {
+    let Pair(a, b) = pair.into_value();
+    NumberVc::cell(a + b)
+}
+
+#[turbo_tasks::function]
+fn label_length(label: TransientInstance<String>) -> NumberVc {
+    let label: Arc<String> = label.into();
+    NumberVc::cell(label.len() as u32)
+}
@@ -0,0 +1,107 @@
+#![feature(min_specialization)]
+
+use std::time::Duration;
+
+use futures::{future::try_join_all, FutureExt, StreamExt};
+use turbo_tasks::TurboTasks;
+use turbo_tasks_memory::MemoryBackend;
+use turbo_tasks_testing::register;
+
+register!();
+
+#[tokio::test]
+async fn wait_for_update_info_without_scheduled_tasks() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+
+    // Nothing has been scheduled yet, so this must wait instead of panicking
+    let result = tokio::time::timeout(
+        Duration::from_millis(50),
+        tt.get_or_wait_update_info(Duration::ZERO),
+    )
+    .await;
+    assert!(result.is_err());
+
+    tt.run_once(async { Ok(()) }).await.unwrap();
+    let (_, count) = tokio::time::timeout(
+        Duration::from_secs(1),
+        tt.get_or_wait_update_info(Duration::ZERO),
+    )
+    .await
+    .unwrap();
+    assert!(count > 0);
+}
+
+#[tokio::test]
+async fn concurrent_aggregating_callers_all_get_an_update() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    let aggregation = Duration::from_millis(10);
+    let callers = (0..2).map(|_| {
+        let tt = tt.clone();
+        tokio::spawn(async move { tt.get_or_wait_update_info(aggregation).await })
+    });
+    let mut callers = Box::pin(try_join_all(callers));
+    tokio::task::yield_now().await;
+
+    // Only one of the callers can take the first update, the other one has to
+    // wait for the next one instead of panicking
+    for _ in 0..20 {
+        tt.run_once(async { Ok(()) }).await.unwrap();
+        if let Ok(results) = tokio::time::timeout(aggregation * 5, &mut callers).await {
+            for (_, count) in results.unwrap() {
+                assert!(count > 0);
+            }
+            return;
+        }
+    }
+    panic!("callers didn't get an update");
+}
+
+#[tokio::test]
+async fn idle_stream_yields_once_per_burst() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    let mut idle = Box::pin(tt.idle_stream());
+
+    for _ in 0..2 {
+        tt.run_once(async { Ok(()) }).await.unwrap();
+        let (_, count) = tokio::time::timeout(Duration::from_secs(1), idle.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(count > 0);
+    }
+}
+
+#[tokio::test]
+async fn idle_stream_is_an_independent_consumer() {
+    *REGISTER;
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.run_once(async { Ok(()) }).await.unwrap();
+    while !tt.is_idle() {
+        tokio::task::yield_now().await;
+    }
+
+    // Updates from before the stream has been created are not yielded
+    let mut idle = Box::pin(tt.idle_stream());
+    assert!(idle.next().now_or_never().is_none());
+
+    // Takes the update of the first burst
+    tt.get_or_wait_update_info(Duration::from_millis(10)).await;
+
+    // Both consumers get the update of the second burst
+    tt.run_once(async { Ok(()) }).await.unwrap();
+    let (_, count) = tokio::time::timeout(Duration::from_secs(1), idle.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(count > 0);
+    let (_, count) = tokio::time::timeout(
+        Duration::from_secs(1),
+        tt.get_or_wait_update_info(Duration::from_millis(10)),
+    )
+    .await
+    .unwrap();
+    assert!(count > 0);
+}
@@ -0,0 +1,54 @@
+#![feature(min_specialization)]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use turbo_tasks::{yield_now, CompletionVc};
+use turbo_tasks_testing::{register, run};
+
+register!();
+
+#[tokio::test]
+async fn yielding_tasks_let_other_tasks_progress() {
+    // The test runtime is single threaded, so each of the tasks would block the
+    // other one forever if it didn't yield
+    run! {
+        let progress = ProgressVc::cell(Progress {
+            started: AtomicBool::new(false),
+            done: AtomicBool::new(false),
+        });
+        let waiting = wait_for_done(progress);
+        let finishing = finish(progress);
+        waiting.await?;
+        finishing.await?;
+        assert!(progress.await?.done.load(Ordering::SeqCst));
+    }
+}
+
+#[turbo_tasks::value(serialization = "none", cell = "new", eq = "manual")]
+struct Progress {
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    started: AtomicBool,
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    done: AtomicBool,
+}
+
+#[turbo_tasks::function]
+async fn wait_for_done(progress: ProgressVc) -> Result<CompletionVc> {
+    let progress = progress.await?;
+    progress.started.store(true, Ordering::SeqCst);
+    while !progress.done.load(Ordering::SeqCst) {
+        yield_now().await;
+    }
+    Ok(CompletionVc::new())
+}
+
+#[turbo_tasks::function]
+async fn finish(progress: ProgressVc) -> Result<CompletionVc> {
+    let progress = progress.await?;
+    while !progress.started.load(Ordering::SeqCst) {
+        yield_now().await;
+    }
+    progress.done.store(true, Ordering::SeqCst);
+    Ok(CompletionVc::new())
+}
@@ -127,6 +127,19 @@ pub struct TaskExecutionSpec {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CellContent(pub Option<SharedReference>);
 
+impl CellContent {
+    /// Estimates the memory held by the content of the cell, see
+    /// [MemorySize](crate::MemorySize).
+    pub fn memory_size(&self) -> usize {
+        match &self.0 {
+            Some(SharedReference(Some(ty), data)) => {
+                registry::get_value_type(*ty).memory_size(&**data)
+            }
+            _ => 0,
+        }
+    }
+}
+
 impl Display for CellContent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.0 {
@@ -162,6 +175,97 @@ impl CellContent {
     }
 }
 
+/// The size of a task graph, see [Backend::graph_size].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GraphSize {
+    /// The number of tasks in the graph.
+    pub tasks: usize,
+    /// The number of cells of all tasks.
+    pub cells: usize,
+    /// A rough estimate of the memory held by tasks and cells. Cell contents
+    /// are not included.
+    pub estimated_bytes: usize,
+}
+
+/// A serializable snapshot of the task graph, e. g. to dump it to JSON and
+/// inspect it with external tooling.
+///
+/// Task inputs are only included as summaries (kind and type). Their values
+/// are never part of the export, so it doesn't leak secrets.
+#[derive(Serialize, Debug, Default)]
+pub struct GraphExport {
+    pub tasks: Vec<TaskNode>,
+}
+
+impl GraphExport {
+    /// Compares this export with an export that was taken `before` it, e. g.
+    /// before a file has changed, to find out which tasks have been
+    /// recomputed.
+    pub fn diff(&self, before: &GraphExport) -> GraphDiff {
+        let before: HashMap<usize, u32> = before
+            .tasks
+            .iter()
+            .map(|task| (task.id, task.executions))
+            .collect();
+        let mut diff = GraphDiff::default();
+        for task in self.tasks.iter() {
+            match before.get(&task.id) {
+                Some(&executions) if task.executions > executions => {
+                    diff.reexecuted.push(TaskChange::new(task))
+                }
+                Some(_) => diff.unchanged += 1,
+                None => diff.added.push(TaskChange::new(task)),
+            }
+        }
+        diff
+    }
+}
+
+/// The tasks that have been recomputed between two [GraphExport]s, see
+/// [GraphExport::diff].
+#[derive(Serialize, Debug, Default)]
+pub struct GraphDiff {
+    /// Tasks that existed before and have been executed again.
+    pub reexecuted: Vec<TaskChange>,
+    /// Tasks that didn't exist before.
+    pub added: Vec<TaskChange>,
+    /// The number of tasks that existed before and haven't been executed
+    /// again.
+    pub unchanged: usize,
+}
+
+/// A task in a [GraphDiff].
+#[derive(Serialize, Debug)]
+pub struct TaskChange {
+    pub id: usize,
+    pub function: String,
+}
+
+impl TaskChange {
+    fn new(task: &TaskNode) -> Self {
+        Self {
+            id: task.id,
+            function: task.function.clone(),
+        }
+    }
+}
+
+/// A single task in a [GraphExport].
+#[derive(Serialize, Debug)]
+pub struct TaskNode {
+    pub id: usize,
+    /// The function (or kind of task) that is executed by this task.
+    pub function: String,
+    /// Summaries of the task inputs.
+    pub inputs: Vec<String>,
+    /// Tasks that have been called by this task.
+    pub children: Vec<usize>,
+    /// Tasks whose output or cells have been read by this task.
+    pub dependencies: Vec<usize>,
+    /// How often the task has been executed since the stats have been reset.
+    pub executions: u32,
+}
+
 pub trait Backend: Sync + Send {
     #[allow(unused_variables)]
     fn initialize(&mut self, task_id_provider: &dyn TaskIdProvider) {}
@@ -324,6 +428,18 @@ pub trait Backend: Sync + Send {
         task_type: TransientTaskType,
         turbo_tasks: &dyn TurboTasksBackendApi,
     ) -> TaskId;
+
+    /// Returns the current size of the task graph. This is cheap enough to be
+    /// polled frequently.
+    fn graph_size(&self) -> GraphSize {
+        GraphSize::default()
+    }
+
+    /// Creates a serializable snapshot of the task graph, see [GraphExport].
+    /// Backends that don't keep the graph in memory return an empty export.
+    fn export_graph(&self) -> GraphExport {
+        GraphExport::default()
+    }
 }
 
 impl PersistentTaskType {
@@ -48,11 +48,13 @@ mod manager;
 mod native_function;
 mod no_move_vec;
 mod nothing;
+mod observer;
 mod once_map;
 pub mod persisted_graph;
 pub mod primitives;
 mod raw_vc;
 mod read_ref;
+mod recording;
 pub mod registry;
 mod task_input;
 mod timed_future;
@@ -72,18 +74,20 @@ pub use id::{
 pub use join_iter_ext::{JoinIterExt, TryJoinIterExt};
 pub use manager::{
     dynamic_call, emit, get_invalidator, run_once, spawn_blocking, spawn_thread, trait_call,
-    turbo_tasks, Invalidator, TaskIdProvider, TurboTasks, TurboTasksApi, TurboTasksBackendApi,
-    TurboTasksCallApi,
+    turbo_tasks, yield_now, Invalidator, TaskIdProvider, TurboTasks, TurboTasksApi,
+    TurboTasksBackendApi, TurboTasksCallApi,
 };
-pub use native_function::{NativeFunction, NativeFunctionVc};
+pub use native_function::{NativeFunction, NativeFunctionCacheStats, NativeFunctionVc};
 pub use nothing::{Nothing, NothingVc};
+pub use observer::{TaskObserver, TaskTransition};
 pub use raw_vc::{CollectiblesFuture, RawVc, ReadRawVcFuture, ResolveTypeError};
 pub use read_ref::ReadRef;
+pub use recording::{CallRecording, RecordedCall};
 pub use task_input::{FromTaskInput, SharedReference, SharedValue, TaskInput};
 pub use turbo_tasks_macros::{function, value, value_impl, value_trait};
 pub use value::{TransientInstance, TransientValue, Value};
 pub use value_type::{
-    TraitMethod, TraitType, Typed, TypedForInput, ValueTraitVc, ValueType, ValueVc,
+    MemorySize, TraitMethod, TraitType, Typed, TypedForInput, ValueTraitVc, ValueType, ValueVc,
 };
 
 #[doc(hidden)]
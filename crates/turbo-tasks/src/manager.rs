@@ -1,7 +1,7 @@
 use std::{
     borrow::Cow,
     cell::RefCell,
-    collections::HashSet,
+    collections::{HashSet, VecDeque},
     fmt::Debug,
     future::Future,
     hash::Hash,
@@ -9,8 +9,8 @@ use std::{
     panic::AssertUnwindSafe,
     pin::Pin,
     sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
-        Arc, Mutex, Weak,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex, RwLock, Weak,
     },
     thread,
     time::{Duration, Instant},
@@ -18,20 +18,25 @@ use std::{
 
 use anyhow::{anyhow, Result};
 use event_listener::{Event, EventListener};
-use futures::FutureExt;
+use futures::{stream, FutureExt, Stream};
 use serde::{de::Visitor, Deserialize, Serialize};
 use tokio::{runtime::Handle, select, task_local};
 
 use crate::{
-    backend::{Backend, CellContent, CellMappings, PersistentTaskType, TransientTaskType},
+    backend::{
+        Backend, CellContent, CellMappings, GraphExport, GraphSize, PersistentTaskType,
+        TransientTaskType,
+    },
     id::{BackendJobId, FunctionId, TraitTypeId},
     id_factory::IdFactory,
     raw_vc::RawVc,
+    recording::{CallRecorder, CallRecording, RecordedCall},
     task_input::{SharedReference, SharedValue, TaskInput},
     timed_future::{self, TimedFuture},
     trace::TraceRawVcs,
     util::FormatDuration,
-    Nothing, NothingVc, TaskId, Typed, TypedForInput, ValueTraitVc, ValueTypeId,
+    Nothing, NothingVc, TaskId, TaskObserver, TaskTransition, Typed, TypedForInput, ValueTraitVc,
+    ValueTypeId,
 };
 
 pub trait TurboTasksCallApi: Sync + Send {
@@ -179,9 +184,34 @@ pub struct TurboTasks<B: Backend + 'static> {
     scheduled_tasks: AtomicUsize,
     start: Mutex<Option<Instant>>,
     aggregated_update: Mutex<Option<(Duration, usize)>>,
+    /// The updates since startup, see [TurboTasks::idle_stream]
+    idle_totals: Mutex<IdleTotals>,
     event: Event,
+    idle_event: Event,
     event_foreground: Event,
     event_background: Event,
+    /// Invalidations within this window (in nanoseconds) are batched. 0
+    /// disables batching.
+    invalidation_batch_window: AtomicU64,
+    pending_invalidations: Mutex<HashSet<TaskId>>,
+    /// Whether `task_observer` is set. Allows to skip the lock without an
+    /// observer.
+    has_task_observer: AtomicBool,
+    task_observer: RwLock<Option<Arc<dyn TaskObserver>>>,
+    /// The maximum number of queue depth samples to keep. 0 disables sampling.
+    queue_sample_capacity: AtomicUsize,
+    queue_samples: Mutex<VecDeque<(Instant, usize)>>,
+    recording: AtomicBool,
+    call_recorder: Mutex<Option<CallRecorder>>,
+}
+
+/// The sum of all updates, see [TurboTasks::idle_stream]
+#[derive(Clone, Copy, Default)]
+struct IdleTotals {
+    /// How often the scheduler became idle
+    idle_count: usize,
+    duration: Duration,
+    tasks: usize,
 }
 
 // TODO implement our own thread pool and make these thread locals instead
@@ -199,6 +229,15 @@ task_local! {
     static TASKS_TO_NOTIFY: RefCell<Vec<TaskId>>;
 }
 
+/// Takes the tracked `TASKS_TO_NOTIFY`. A task that depends on multiple
+/// updated cells is only included once, so it's only notified once.
+fn take_tasks_to_notify(tasks: &RefCell<Vec<TaskId>>) -> Vec<TaskId> {
+    let mut tasks = tasks.take();
+    let mut seen = HashSet::with_capacity(tasks.len());
+    tasks.retain(|task| seen.insert(*task));
+    tasks
+}
+
 impl<B: Backend> TurboTasks<B> {
     // TODO better lifetime management for turbo tasks
     // consider using unsafe for the task_local turbo tasks
@@ -219,9 +258,19 @@ impl<B: Backend> TurboTasks<B> {
             scheduled_tasks: AtomicUsize::new(0),
             start: Default::default(),
             aggregated_update: Default::default(),
+            idle_totals: Default::default(),
             event: Event::new(),
+            idle_event: Event::new(),
             event_foreground: Event::new(),
             event_background: Event::new(),
+            invalidation_batch_window: AtomicU64::new(0),
+            pending_invalidations: Default::default(),
+            has_task_observer: AtomicBool::new(false),
+            task_observer: RwLock::new(None),
+            queue_sample_capacity: AtomicUsize::new(0),
+            queue_samples: Default::default(),
+            recording: AtomicBool::new(false),
+            call_recorder: Default::default(),
         });
         this.backend.startup(&*this);
         this
@@ -242,6 +291,7 @@ impl<B: Backend> TurboTasks<B> {
         let id = self
             .backend
             .create_transient_task(TransientTaskType::Root(Box::new(functor)), self);
+        self.record_root_task(id);
         self.schedule(id);
         id
     }
@@ -256,6 +306,7 @@ impl<B: Backend> TurboTasks<B> {
         let id = self
             .backend
             .create_transient_task(TransientTaskType::Once(Box::pin(future)), self);
+        self.record_root_task(id);
         self.schedule(id);
         id
     }
@@ -291,6 +342,9 @@ impl<B: Backend> TurboTasks<B> {
     /// Calls a native function with arguments. Resolves arguments when needed
     /// with a wrapper [Task].
     pub fn dynamic_call(&self, func: FunctionId, inputs: Vec<TaskInput>) -> RawVc {
+        if self.recording.load(Ordering::Acquire) {
+            self.record_call(func, &inputs);
+        }
         if inputs.iter().all(|i| i.is_resolved() && !i.is_nothing()) {
             self.native_call(func, inputs)
         } else {
@@ -317,9 +371,28 @@ impl<B: Backend> TurboTasks<B> {
         ))
     }
 
+    /// Registers an observer that is notified about every task lifecycle
+    /// transition. Replaces a previously registered observer.
+    pub fn set_task_observer(&self, observer: Option<Arc<dyn TaskObserver>>) {
+        let mut task_observer = self.task_observer.write().unwrap();
+        self.has_task_observer
+            .store(observer.is_some(), Ordering::Release);
+        *task_observer = observer;
+    }
+
+    fn notify_task_observer(&self, task: TaskId, transition: TaskTransition) {
+        if !self.has_task_observer.load(Ordering::Acquire) {
+            return;
+        }
+        if let Some(observer) = &*self.task_observer.read().unwrap() {
+            observer.on_transition(task, transition);
+        }
+    }
+
     pub(crate) fn schedule(&self, task_id: TaskId) {
         self.begin_primary_job();
         self.scheduled_tasks.fetch_add(1, Ordering::AcqRel);
+        self.notify_task_observer(task_id, TaskTransition::Scheduled);
 
         #[cfg(feature = "tokio_tracing")]
         let description = self.backend.get_task_description(task_id);
@@ -331,6 +404,7 @@ impl<B: Backend> TurboTasks<B> {
                     break;
                 }
                 if let Some(execution) = this.backend.try_start_task_execution(task_id, &*this) {
+                    this.notify_task_observer(task_id, TaskTransition::ExecutionStarted);
                     // Setup thread locals
                     let has_cell_mappings = execution.cell_mappings.is_some();
 
@@ -362,7 +436,14 @@ impl<B: Backend> TurboTasks<B> {
                             Err(_) => None,
                         },
                     });
+                    let transition = if matches!(result, Ok(Ok(_))) {
+                        TaskTransition::Completed
+                    } else {
+                        TaskTransition::Errored
+                    };
                     this.backend.task_execution_result(task_id, result, &*this);
+                    // Notify after the output has been stored, so the observer can read it
+                    this.notify_task_observer(task_id, transition);
                     this.notify_scheduled_tasks_internal();
                     let reexecute = this.backend.task_execution_completed(
                         task_id,
@@ -399,13 +480,13 @@ impl<B: Backend> TurboTasks<B> {
     }
 
     fn begin_primary_job(&self) {
-        if self
+        let in_progress = self
             .currently_scheduled_tasks
-            .fetch_add(1, Ordering::AcqRel)
-            == 0
-        {
+            .fetch_add(1, Ordering::AcqRel);
+        if in_progress == 0 {
             *self.start.lock().unwrap() = Some(Instant::now());
         }
+        self.sample_queue_depth(in_progress + 1);
     }
 
     fn begin_foreground_job(&self) {
@@ -415,25 +496,32 @@ impl<B: Backend> TurboTasks<B> {
     }
 
     fn finish_primary_job(&self) {
-        if self
+        let in_progress = self
             .currently_scheduled_tasks
-            .fetch_sub(1, Ordering::AcqRel)
-            == 1
-        {
+            .fetch_sub(1, Ordering::AcqRel);
+        self.sample_queue_depth(in_progress - 1);
+        if in_progress == 1 {
             // That's not super race-condition-safe, but it's only for
             // statistical reasons
             let total = self.scheduled_tasks.load(Ordering::Acquire);
             self.scheduled_tasks.store(0, Ordering::Release);
             if let Some(start) = *self.start.lock().unwrap() {
+                let elapsed = start.elapsed();
                 let mut update = self.aggregated_update.lock().unwrap();
                 if let Some(update) = update.as_mut() {
-                    update.0 += start.elapsed();
+                    update.0 += elapsed;
                     update.1 += total;
                 } else {
-                    *update = Some((start.elapsed(), total));
+                    *update = Some((elapsed, total));
                 }
+                drop(update);
+                let mut totals = self.idle_totals.lock().unwrap();
+                totals.idle_count += 1;
+                totals.duration += elapsed;
+                totals.tasks += total;
             }
             self.event.notify(usize::MAX);
+            self.idle_event.notify(usize::MAX);
         }
     }
 
@@ -460,17 +548,58 @@ impl<B: Backend> TurboTasks<B> {
         if self
             .currently_scheduled_foreground_jobs
             .load(Ordering::Acquire)
-            != 0
+            == 0
         {
             return;
         }
         listener.await;
     }
 
+    /// Returns the number of tasks and foreground jobs that are currently
+    /// scheduled or executing.
     pub fn get_in_progress_count(&self) -> usize {
         self.currently_scheduled_tasks.load(Ordering::Acquire)
     }
 
+    /// Returns true when no tasks or foreground jobs are scheduled or
+    /// executing. This can be used to back a readiness probe.
+    ///
+    /// Note that idleness can be transient: any invalidation (e. g. a file
+    /// change) will schedule new work immediately.
+    pub fn is_idle(&self) -> bool {
+        self.get_in_progress_count() == 0
+    }
+
+    /// Keeps the last `capacity` samples of the number of in progress tasks and
+    /// foreground jobs. A sample is taken whenever work is scheduled or
+    /// finished. A `capacity` of 0 (the default) disables sampling.
+    pub fn set_queue_sample_capacity(&self, capacity: usize) {
+        self.queue_sample_capacity
+            .store(capacity, Ordering::Release);
+        let mut samples = self.queue_samples.lock().unwrap();
+        while samples.len() > capacity {
+            samples.pop_front();
+        }
+    }
+
+    /// Returns the recent queue depth samples as (time, in progress count)
+    /// pairs, oldest first. See [TurboTasks::set_queue_sample_capacity].
+    pub fn queue_depth_samples(&self) -> Vec<(Instant, usize)> {
+        self.queue_samples.lock().unwrap().iter().copied().collect()
+    }
+
+    fn sample_queue_depth(&self, in_progress: usize) {
+        let capacity = self.queue_sample_capacity.load(Ordering::Acquire);
+        if capacity == 0 {
+            return;
+        }
+        let mut samples = self.queue_samples.lock().unwrap();
+        while samples.len() >= capacity {
+            samples.pop_front();
+        }
+        samples.push_back((Instant::now(), in_progress));
+    }
+
     pub async fn wait_task_completion(&self, id: TaskId, fully_settled: bool) -> Result<()> {
         // INVALIDATION: This doesn't return a value, only waits for it to be ready.
         let result = read_task_output_untracked(self, id, fully_settled).await;
@@ -499,8 +628,43 @@ impl<B: Backend> TurboTasks<B> {
                 }
             }
         }
-        // TODO(alexkirsz) The take unwrap crashed on me.
-        return self.aggregated_update.lock().unwrap().take().unwrap();
+        // The update might have been taken by a concurrent caller in the meantime, or
+        // no task has completed so far. Wait for the next one instead of panicking.
+        self.take_update_info().await
+    }
+
+    /// Waits for the next update, if there is none yet, and takes it.
+    async fn take_update_info(&self) -> (Duration, usize) {
+        loop {
+            let listener = self.event.listen();
+            if let Some(info) = self.aggregated_update.lock().unwrap().take() {
+                return info;
+            }
+            listener.await;
+        }
+    }
+
+    /// Returns a stream that yields the aggregated update info (duration and
+    /// number of executed tasks) every time the scheduler becomes idle. Updates
+    /// that happen while the stream isn't polled are merged into one item.
+    ///
+    /// The stream only yields updates that happen after it has been created.
+    /// Every stream sees all updates, independent of other streams and of
+    /// [TurboTasks::get_or_wait_update_info].
+    pub fn idle_stream(&self) -> impl Stream<Item = (Duration, usize)> {
+        let seen = *self.idle_totals.lock().unwrap();
+        stream::unfold((self.pin(), seen), |(this, seen)| async move {
+            let totals = loop {
+                let listener = this.idle_event.listen();
+                let totals = *this.idle_totals.lock().unwrap();
+                if totals.idle_count != seen.idle_count {
+                    break totals;
+                }
+                listener.await;
+            };
+            let info = (totals.duration - seen.duration, totals.tasks - seen.tasks);
+            Some((info, (this, totals)))
+        })
     }
 
     pub async fn wait_background_done(&self) {
@@ -585,7 +749,7 @@ impl<B: Backend> TurboTasks<B> {
 
     fn notify_scheduled_tasks_internal(&self) {
         TASKS_TO_NOTIFY.with(|tasks| {
-            let tasks = tasks.take();
+            let tasks = take_tasks_to_notify(tasks);
             if tasks.is_empty() {
                 return;
             }
@@ -593,9 +757,95 @@ impl<B: Backend> TurboTasks<B> {
         });
     }
 
+    /// Batches invalidations that arrive within `window` after the first one
+    /// and applies them together. This avoids rebuild storms when many files
+    /// change at once (e. g. "save all" in an editor).
+    ///
+    /// A zero `window` (the default) applies invalidations immediately.
+    pub fn set_invalidation_batch_window(&self, window: Duration) {
+        self.invalidation_batch_window
+            .store(window.as_nanos() as u64, Ordering::Release);
+    }
+
+    fn flush_pending_invalidations(&self) {
+        let tasks = take(&mut *self.pending_invalidations.lock().unwrap());
+        if !tasks.is_empty() {
+            self.backend
+                .invalidate_tasks(tasks.into_iter().collect(), self);
+        }
+        // Pending invalidations count as a foreground job, see `invalidate`
+        self.finish_foreground_job();
+    }
+
     pub fn backend(&self) -> &B {
         &self.backend
     }
+
+    /// Returns the current size of the task graph, see [Backend::graph_size].
+    pub fn graph_size(&self) -> GraphSize {
+        self.backend.graph_size()
+    }
+
+    /// Creates a serializable snapshot of the task graph, see
+    /// [Backend::export_graph].
+    pub fn export_graph(&self) -> GraphExport {
+        self.backend.export_graph()
+    }
+
+    /// Starts recording the calls that root tasks spawned from now on make.
+    /// See [CallRecording].
+    pub fn start_recording(&self) {
+        *self.call_recorder.lock().unwrap() = Some(CallRecorder::default());
+        self.recording.store(true, Ordering::Release);
+    }
+
+    /// Stops recording and returns the calls recorded since
+    /// [TurboTasks::start_recording].
+    pub fn stop_recording(&self) -> CallRecording {
+        self.recording.store(false, Ordering::Release);
+        self.call_recorder
+            .lock()
+            .unwrap()
+            .take()
+            .map(|recorder| recorder.recording)
+            .unwrap_or_default()
+    }
+
+    fn record_root_task(&self, task: TaskId) {
+        if self.recording.load(Ordering::Acquire) {
+            if let Some(recorder) = &mut *self.call_recorder.lock().unwrap() {
+                recorder.root_tasks.insert(task);
+            }
+        }
+    }
+
+    fn record_call(&self, func: FunctionId, inputs: &[TaskInput]) {
+        let parent = try_current_task();
+        if let Some(recorder) = &mut *self.call_recorder.lock().unwrap() {
+            // Calls without a parent task are made from the top level, like the
+            // calls of root tasks
+            if parent.map_or(true, |parent| recorder.root_tasks.contains(&parent)) {
+                recorder.record(func, inputs);
+            }
+        }
+    }
+
+    /// Makes the calls of a [CallRecording] in order. Each call is awaited
+    /// before the next one is made. Returns the resolved outputs of the calls.
+    pub async fn replay(&self, recording: CallRecording) -> Result<Vec<RawVc>> {
+        self.run_once(async move {
+            let mut outputs = Vec::with_capacity(recording.calls.len());
+            for RecordedCall { function, inputs } in recording.calls {
+                outputs.push(
+                    dynamic_call(function, inputs)
+                        .resolve_strongly_consistent()
+                        .await?,
+                );
+            }
+            Ok(outputs)
+        })
+        .await
+    }
 }
 
 impl<B: Backend> TurboTasksCallApi for TurboTasks<B> {
@@ -640,12 +890,31 @@ impl<B: Backend> TurboTasksCallApi for TurboTasks<B> {
 
 impl<B: Backend> TurboTasksApi for TurboTasks<B> {
     fn invalidate(&self, task: TaskId) {
-        self.backend.invalidate_task(task, self);
+        let window = self.invalidation_batch_window.load(Ordering::Acquire);
+        if window == 0 {
+            self.backend.invalidate_task(task, self);
+            return;
+        }
+        let mut pending = self.pending_invalidations.lock().unwrap();
+        let first = pending.is_empty();
+        pending.insert(task);
+        drop(pending);
+        if first {
+            // Pending invalidations are a foreground job, so that the scheduler
+            // isn't considered idle and strongly consistent reads wait until
+            // they have been applied
+            self.begin_foreground_job();
+            let this = self.pin();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_nanos(window)).await;
+                this.flush_pending_invalidations();
+            });
+        }
     }
 
     fn notify_scheduled_tasks(&self) {
         let _ = TASKS_TO_NOTIFY.try_with(|tasks| {
-            let tasks = tasks.take();
+            let tasks = take_tasks_to_notify(tasks);
             if tasks.is_empty() {
                 return;
             }
@@ -789,7 +1058,7 @@ impl<B: Backend> TurboTasksBackendApi for TurboTasks<B> {
         if self
             .currently_scheduled_foreground_jobs
             .load(Ordering::Acquire)
-            != 0
+            == 0
         {
             return Ok(());
         }
@@ -836,10 +1105,14 @@ impl<B: Backend> TaskIdProvider for TurboTasks<B> {
     }
 }
 
+pub(crate) fn try_current_task() -> Option<TaskId> {
+    CURRENT_TASK_ID.try_with(|id| *id).ok()
+}
+
 fn current_task(from: &str) -> TaskId {
-    match CURRENT_TASK_ID.try_with(|id| *id) {
-        Ok(id) => id,
-        Err(_) => panic!(
+    match try_current_task() {
+        Some(id) => id,
+        None => panic!(
             "{} can only be used in the context of turbo_tasks task execution",
             from
         ),
@@ -873,7 +1146,7 @@ impl Invalidator {
             turbo_tasks,
             handle,
         } = self;
-        let _ = handle.enter();
+        let _guard = handle.enter();
         if let Some(turbo_tasks) = turbo_tasks.upgrade() {
             turbo_tasks.invalidate(task);
         }
@@ -997,6 +1270,9 @@ pub fn emit<T: ValueTraitVc>(collectible: T) {
     with_turbo_tasks(|tt| tt.emit_collectible(T::get_trait_type_id(), collectible.into()))
 }
 
+/// Runs a synchronous, CPU heavy `func` on a thread for blocking work, so it
+/// doesn't block the async workers that execute other tasks. The time spent
+/// is still accounted to the current task.
 pub async fn spawn_blocking<T: Send + 'static>(func: impl FnOnce() -> T + Send + 'static) -> T {
     let (r, d) = tokio::task::spawn_blocking(|| {
         let start = Instant::now();
@@ -1009,6 +1285,13 @@ pub async fn spawn_blocking<T: Send + 'static>(func: impl FnOnce() -> T + Send +
     r
 }
 
+/// Yields execution back to the scheduler, so other tasks can make progress.
+/// Long running functions that can't be moved into [spawn_blocking] should
+/// call this periodically, e. g. once per processed item.
+pub async fn yield_now() {
+    tokio::task::yield_now().await
+}
+
 pub fn spawn_thread(func: impl FnOnce() + Send + 'static) {
     let handle = Handle::current();
     thread::spawn(move || {
@@ -1201,3 +1484,22 @@ pub fn find_cell_by_type(type_id: ValueTypeId) -> CurrentCellRef {
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::take_tasks_to_notify;
+    use crate::TaskId;
+
+    #[test]
+    fn tasks_to_notify_are_deduplicated() {
+        let ids = [3, 1, 3, 2, 1, 3];
+        let tasks = RefCell::new(ids.into_iter().map(TaskId::from).collect());
+        assert_eq!(
+            take_tasks_to_notify(&tasks),
+            [3, 1, 2].into_iter().map(TaskId::from).collect::<Vec<_>>()
+        );
+        assert!(tasks.borrow().is_empty());
+    }
+}
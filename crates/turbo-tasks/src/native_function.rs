@@ -30,6 +30,32 @@ pub struct NativeFunction {
     /// A counter that tracks total executions of that function
     #[turbo_tasks(debug_ignore, trace_ignore)]
     pub executed_count: AtomicUsize,
+    /// A counter that tracks how often a call was served by an existing task
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    pub cache_hit_count: AtomicUsize,
+    /// A counter that tracks how often a call needed a new task
+    #[turbo_tasks(debug_ignore, trace_ignore)]
+    pub cache_miss_count: AtomicUsize,
+}
+
+/// A snapshot of the cache counters of a [NativeFunction].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NativeFunctionCacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl NativeFunctionCacheStats {
+    /// The ratio of calls that have been served by an existing task. A very
+    /// low ratio hints that the function inputs are not stable.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
 }
 
 impl Debug for NativeFunction {
@@ -49,6 +75,33 @@ impl NativeFunction {
             name,
             bind_fn: Box::new(bind_fn),
             executed_count: AtomicUsize::new(0),
+            cache_hit_count: AtomicUsize::new(0),
+            cache_miss_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Records a call that was served by an existing task. Only tracked with
+    /// the `log_function_stats` feature.
+    pub fn record_cache_hit(&self) {
+        if cfg!(feature = "log_function_stats") {
+            self.cache_hit_count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records a call that needed a new task. Only tracked with the
+    /// `log_function_stats` feature.
+    pub fn record_cache_miss(&self) {
+        if cfg!(feature = "log_function_stats") {
+            self.cache_miss_count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns the current cache counters. They are always zero without the
+    /// `log_function_stats` feature.
+    pub fn cache_stats(&self) -> NativeFunctionCacheStats {
+        NativeFunctionCacheStats {
+            hits: self.cache_hit_count.load(Ordering::Relaxed),
+            misses: self.cache_miss_count.load(Ordering::Relaxed),
         }
     }
 
@@ -0,0 +1,22 @@
+use crate::TaskId;
+
+/// A lifecycle transition of a task, as reported to a [TaskObserver].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaskTransition {
+    /// The task has been scheduled for execution.
+    Scheduled,
+    /// The task execution has started.
+    ExecutionStarted,
+    /// The task execution has finished successfully.
+    Completed,
+    /// The task execution has finished with an error or a panic.
+    Errored,
+}
+
+/// Receives task lifecycle transitions, e. g. to drive a live dashboard.
+///
+/// It's called synchronously from the scheduler, so implementations should be
+/// cheap and must not block.
+pub trait TaskObserver: Send + Sync {
+    fn on_transition(&self, task: TaskId, transition: TaskTransition);
+}
@@ -19,7 +19,8 @@ use crate::{
     backend::CellContent,
     manager::{
         find_cell_by_key, find_cell_by_type, read_task_cell, read_task_cell_untracked,
-        read_task_output, read_task_output_untracked, CurrentCellRef, TurboTasksApi,
+        read_task_output, read_task_output_untracked, try_current_task, CurrentCellRef,
+        TurboTasksApi,
     },
     primitives::{RawVcSet, RawVcSetVc},
     registry::get_value_type,
@@ -107,6 +108,77 @@ impl RawVc {
             .cast::<T>()
     }
 
+    /// Reads the value synchronously, but only when it is already available.
+    ///
+    /// This never drives computation and never waits for a task. It follows
+    /// the chain of task outputs as long as they are already computed and
+    /// returns the content of the cell at the end. When any task on the way,
+    /// including the task owning the cell, is not done yet, has failed or the
+    /// cell is empty, it returns `None`. Only the cells of the current task
+    /// can be read while it's executing.
+    ///
+    /// This is useful in non-async code like `Drop` implementations.
+    ///
+    /// INVALIDATION: Be careful with this, it will not track dependencies, so
+    /// using it could break cache invalidation.
+    pub fn try_read_sync_untracked<T: Any + Send + Sync>(
+        self,
+        turbo_tasks: &dyn TurboTasksApi,
+    ) -> Option<ReadRef<T>> {
+        self.try_read_sync_untracked_internal(turbo_tasks)?
+            .try_cast::<T>()
+    }
+
+    /// see [RawVc::try_read_sync_untracked]
+    ///
+    /// # Safety
+    ///
+    /// T and U must be binary identical (#[repr(transparent)])
+    pub unsafe fn try_transparent_read_sync_untracked<
+        T: Any + Send + Sync,
+        U: Any + Send + Sync,
+    >(
+        self,
+        turbo_tasks: &dyn TurboTasksApi,
+    ) -> Option<ReadRef<T, U>> {
+        let content = self.try_read_sync_untracked_internal(turbo_tasks)?;
+        unsafe { content.cast_transparent::<T, U>() }.ok()
+    }
+
+    fn try_read_sync_untracked_internal(
+        self,
+        turbo_tasks: &dyn TurboTasksApi,
+    ) -> Option<CellContent> {
+        let mut current = self;
+        loop {
+            match current {
+                RawVc::TaskOutput(task) => {
+                    match turbo_tasks.try_read_task_output_untracked(task, false) {
+                        Ok(Ok(vc)) => current = vc,
+                        // Not computed yet or failed. We don't wait for it.
+                        Ok(Err(_)) | Err(_) => return None,
+                    }
+                }
+                RawVc::TaskCell(task, index) => {
+                    // The cells of a task that is dirty or still executing might be
+                    // outdated. Only the current task can read its own cells then.
+                    if try_current_task() != Some(task)
+                        && !matches!(
+                            turbo_tasks.try_read_task_output_untracked(task, false),
+                            Ok(Ok(_))
+                        )
+                    {
+                        return None;
+                    }
+                    return turbo_tasks
+                        .try_read_task_cell_untracked(task, index)
+                        .ok()?
+                        .ok();
+                }
+            }
+        }
+    }
+
     /// Returns the hash of the pointer that holds the Vc's current data. This
     /// value will change every time the TaskCell recomputes.
     ///
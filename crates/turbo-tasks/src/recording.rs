@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    registry,
+    task_input::{SharedReference, SharedValue},
+    FunctionId, TaskId, TaskInput,
+};
+
+/// The calls that root tasks made while recording, see
+/// [TurboTasks::start_recording](crate::TurboTasks::start_recording).
+///
+/// A recording can be serialized to a file and replayed later with
+/// [TurboTasks::replay](crate::TurboTasks::replay) against a fresh instance to
+/// reproduce a build.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct CallRecording {
+    /// The recorded calls in the order they have been made.
+    pub calls: Vec<RecordedCall>,
+    /// The number of calls that haven't been recorded since their inputs
+    /// refer to other tasks, to transient values or to values that can't be
+    /// serialized, which can't be replayed.
+    pub skipped: usize,
+}
+
+/// A single call of a [NativeFunction](crate::NativeFunction) made by a root
+/// task.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordedCall {
+    pub function: FunctionId,
+    pub inputs: Vec<TaskInput>,
+}
+
+#[derive(Default)]
+pub(crate) struct CallRecorder {
+    /// Root tasks that have been spawned while recording.
+    pub root_tasks: HashSet<TaskId>,
+    pub recording: CallRecording,
+}
+
+impl CallRecorder {
+    pub fn record(&mut self, function: FunctionId, inputs: &[TaskInput]) {
+        if inputs.iter().all(is_replayable) {
+            self.recording.calls.push(RecordedCall {
+                function,
+                inputs: inputs.to_vec(),
+            });
+        } else {
+            self.recording.skipped += 1;
+        }
+    }
+}
+
+fn is_replayable(input: &TaskInput) -> bool {
+    match input {
+        TaskInput::TaskOutput(_) | TaskInput::TaskCell(..) | TaskInput::TransientSharedValue(_) => {
+            false
+        }
+        // Values need to roundtrip through the serialized recording
+        TaskInput::SharedValue(SharedValue(ty, _)) => ty.map_or(false, |ty| {
+            registry::get_value_type(ty)
+                .get_magic_deserialize_seed()
+                .is_some()
+        }),
+        TaskInput::SharedReference(SharedReference(ty, _)) => ty.map_or(false, |ty| {
+            registry::get_value_type(ty)
+                .get_any_deserialize_seed()
+                .is_some()
+        }),
+        TaskInput::List(list) => list.iter().all(is_replayable),
+        _ => true,
+    }
+}
@@ -92,6 +92,22 @@ pub fn get_function_global_name(id: FunctionId) -> &'static str {
     &FUNCTIONS.get(*id).unwrap().1
 }
 
+/// Looks up a function by the name of the crate that registered it and its
+/// path within that crate, e. g. `module::TypeVc::method`. Other than the
+/// global name, this doesn't depend on the hash of the crate.
+pub fn get_function_by_path(crate_name: &str, path: &str) -> Option<&'static NativeFunction> {
+    FUNCTIONS_BY_NAME
+        .pin()
+        .iter()
+        .find_map(|(global_name, id)| {
+            // Global names have the format `{crate_name}@{hash}::{path}`
+            let (name, rest) = global_name.split_once('@')?;
+            let (_hash, function_path) = rest.split_once("::")?;
+            (name == crate_name && function_path.trim_start_matches(':') == path)
+                .then(|| get_function(*id))
+        })
+}
+
 pub fn register_value_type(global_name: &str, ty: &'static ValueType) {
     register_thing(
         global_name,
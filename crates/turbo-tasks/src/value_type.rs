@@ -58,6 +58,27 @@ pub trait TypedForInput: Typed {}
 
 type MagicSerializationFn = fn(&dyn MagicAny) -> &dyn erased_serde::Serialize;
 type AnySerializationFn = fn(&(dyn Any + Sync + Send)) -> &dyn erased_serde::Serialize;
+type MemorySizeFn = fn(&(dyn Any + Sync + Send)) -> usize;
+
+/// An estimate of the memory held by a value, including its heap allocations.
+/// It's used to attribute the memory of cells to the tasks that created them.
+///
+/// Defaults to the size of the type itself. Values that own large allocations
+/// (e. g. buffers) should implement it.
+pub trait MemorySize {
+    fn memory_size(&self) -> usize;
+}
+
+impl<T> MemorySize for T {
+    default fn memory_size(&self) -> usize {
+        std::mem::size_of::<T>()
+    }
+}
+
+fn any_memory_size<T: Any>(this: &(dyn Any + Sync + Send)) -> usize {
+    this.downcast_ref::<T>()
+        .map_or(0, |value| value.memory_size())
+}
 
 // TODO this type need some refactoring when multiple languages are added to
 // turbo-task In this case a trait_method might be of a different function type.
@@ -79,6 +100,8 @@ pub struct ValueType {
     /// Functors for serialization
     magic_serialization: Option<(MagicSerializationFn, MagicAnyDeserializeSeed)>,
     any_serialization: Option<(AnySerializationFn, AnyDeserializeSeed)>,
+
+    memory_size: MemorySizeFn,
 }
 
 impl Hash for ValueType {
@@ -131,13 +154,14 @@ pub fn any_as_serialize<T: Any + Serialize + Send + Sync + 'static>(
 
 impl ValueType {
     /// This is internally used by `#[turbo_tasks::value]`
-    pub fn new<T>() -> Self {
+    pub fn new<T: Any>() -> Self {
         Self {
             name: std::any::type_name::<T>().to_string(),
             traits: HashSet::new(),
             trait_methods: HashMap::new(),
             magic_serialization: None,
             any_serialization: None,
+            memory_size: any_memory_size::<T>,
         }
     }
 
@@ -154,6 +178,7 @@ impl ValueType {
                 MagicAnyDeserializeSeed::new::<T>(),
             )),
             any_serialization: Some((any_as_serialize::<T>, AnyDeserializeSeed::new::<T>())),
+            memory_size: any_memory_size::<T>,
         }
     }
 
@@ -167,9 +192,16 @@ impl ValueType {
             trait_methods: HashMap::new(),
             magic_serialization: None,
             any_serialization: Some((any_as_serialize::<T>, AnyDeserializeSeed::new::<T>())),
+            memory_size: any_memory_size::<T>,
         }
     }
 
+    /// Estimates the memory held by `value`, which must be of this type. See
+    /// [MemorySize].
+    pub fn memory_size(&self, value: &(dyn Any + Sync + Send)) -> usize {
+        (self.memory_size)(value)
+    }
+
     pub fn magic_as_serializable<'a>(
         &self,
         arc: &'a Arc<dyn MagicAny>,
@@ -1,4 +1,8 @@
-use std::{collections::BTreeMap, fmt::Display, mem::take};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    mem::take,
+};
 
 use indexmap::{IndexMap, IndexSet};
 use once_cell::sync::Lazy;
@@ -97,6 +101,10 @@ pub(crate) struct ImportMap {
     /// Ordered list of (module path, annotations)
     references: IndexSet<(JsWord, ImportAnnotations)>,
 
+    /// Map from index in references to the module type asserted by an import,
+    /// e. g. `json` for `assert { type: "json" }`
+    assert_types: HashMap<usize, JsWord>,
+
     /// True, when the module has exports
     has_exports: bool,
 }
@@ -146,6 +154,21 @@ impl ImportMap {
         self.reexports.iter().map(|(i, r)| (*i, r))
     }
 
+    /// Returns true, when the reference at `index` is neither bound to an
+    /// identifier nor reexported, e. g. `import "./polyfill"`. Such a module
+    /// only needs to be evaluated for its side effects.
+    pub fn is_side_effect_only(&self, index: usize) -> bool {
+        !self.imports.values().any(|(i, _)| *i == index)
+            && !self.namespace_imports.values().any(|i| *i == index)
+            && !self.reexports.iter().any(|(i, _)| *i == index)
+    }
+
+    /// Returns the module type that an import of the reference at `index`
+    /// asserts, e. g. `json` for `assert { type: "json" }`.
+    pub fn assert_type(&self, index: usize) -> Option<&str> {
+        self.assert_types.get(&index).map(|ty| &**ty)
+    }
+
     /// Analyze ES import
     pub(super) fn analyze(m: &Program) -> Self {
         let mut data = ImportMap::default();
@@ -165,18 +188,40 @@ struct Analyzer<'a> {
 }
 
 impl<'a> Analyzer<'a> {
-    fn ensure_reference(&mut self, module_path: JsWord) -> usize {
+    fn ensure_reference(&mut self, module_path: JsWord, asserts: Option<&ObjectLit>) -> usize {
         let tuple = (module_path, take(&mut self.current_annotations));
-        if let Some(i) = self.data.references.get_index_of(&tuple) {
+        let i = if let Some(i) = self.data.references.get_index_of(&tuple) {
             i
         } else {
             let i = self.data.references.len();
             self.data.references.insert(tuple);
             i
+        };
+        if let Some(ty) = asserts.and_then(assert_type) {
+            self.data.assert_types.entry(i).or_insert(ty);
         }
+        i
     }
 }
 
+/// Returns the value of the `type` key of an import assertion
+fn assert_type(asserts: &ObjectLit) -> Option<JsWord> {
+    asserts.props.iter().find_map(|prop| match prop {
+        PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp { key, value })) => {
+            let is_type = match key {
+                PropName::Ident(ident) => &*ident.sym == "type",
+                PropName::Str(key) => &*key.value == "type",
+                _ => false,
+            };
+            match &**value {
+                Expr::Lit(Lit::Str(value)) if is_type => Some(value.value.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
 fn to_word(name: &ModuleExportName) -> JsWord {
     match name {
         ModuleExportName::Ident(ident) => ident.sym.clone(),
@@ -220,7 +265,7 @@ impl Visit for Analyzer<'_> {
     }
 
     fn visit_import_decl(&mut self, import: &ImportDecl) {
-        let i = self.ensure_reference(import.src.value.clone());
+        let i = self.ensure_reference(import.src.value.clone(), import.asserts.as_deref());
         for s in &import.specifiers {
             let (local, orig_sym) = match s {
                 ImportSpecifier::Named(ImportNamedSpecifier {
@@ -242,14 +287,14 @@ impl Visit for Analyzer<'_> {
 
     fn visit_export_all(&mut self, export: &ExportAll) {
         self.data.has_exports = true;
-        let i = self.ensure_reference(export.src.value.clone());
+        let i = self.ensure_reference(export.src.value.clone(), export.asserts.as_deref());
         self.data.reexports.push((i, Reexport::Star));
     }
 
     fn visit_named_export(&mut self, export: &NamedExport) {
         self.data.has_exports = true;
         if let Some(ref src) = export.src {
-            let i = self.ensure_reference(src.value.clone());
+            let i = self.ensure_reference(src.value.clone(), export.asserts.as_deref());
             for spec in export.specifiers.iter() {
                 match spec {
                     ExportSpecifier::Namespace(n) => {
@@ -303,3 +348,93 @@ fn orig_name(n: &ModuleExportName) -> JsWord {
         ModuleExportName::Str(v) => v.value.clone(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use swc_core::{
+        common::{sync::Lrc, FileName, SourceMap},
+        ecma::{
+            ast::EsVersion,
+            parser::{parse_file_as_program, EsConfig, Syntax},
+        },
+    };
+
+    use super::ImportMap;
+
+    fn analyze(code: &str) -> ImportMap {
+        let cm: Lrc<SourceMap> = Default::default();
+        let fm = cm.new_source_file(FileName::Anon, code.into());
+        let program = parse_file_as_program(
+            &fm,
+            Syntax::Es(EsConfig {
+                import_assertions: true,
+                ..Default::default()
+            }),
+            EsVersion::latest(),
+            None,
+            &mut vec![],
+        )
+        .unwrap();
+        ImportMap::analyze(&program)
+    }
+
+    #[test]
+    fn side_effect_only_imports() {
+        let map = analyze(
+            r#"
+import "./polyfill";
+import { a } from "./bound";
+import * as ns from "./namespace";
+export * from "./reexport";
+"#,
+        );
+        let references = map.references().map(|(m, _)| &**m).collect::<Vec<_>>();
+        assert_eq!(
+            references,
+            ["./polyfill", "./bound", "./namespace", "./reexport"]
+        );
+        assert!(map.is_side_effect_only(0));
+        assert!(!map.is_side_effect_only(1));
+        assert!(!map.is_side_effect_only(2));
+        assert!(!map.is_side_effect_only(3));
+    }
+
+    #[test]
+    fn import_assertion_type() {
+        let map = analyze(
+            r#"
+import data from "./data.json" assert { type: "json" };
+import other from "./other";
+"#,
+        );
+        let types = (0..map.references().count())
+            .map(|i| map.assert_type(i))
+            .collect::<Vec<_>>();
+        assert_eq!(types, [Some("json"), None]);
+    }
+
+    #[test]
+    fn import_assertion_type_is_not_an_annotation() {
+        let map = analyze(
+            r#"
+import data from "./data.json" assert { type: "json" };
+import { value } from "./data.json";
+"TURBOPACK { type: json }";
+import other from "./other";
+"#,
+        );
+        let references = map
+            .references()
+            .map(|(m, annotations)| (&**m, annotations.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            references,
+            [
+                ("./data.json", "{}".to_string()),
+                ("./other", "{ type: json }".to_string())
+            ]
+        );
+        assert_eq!(map.assert_type(0), Some("json"));
+        assert_eq!(map.assert_type(1), None);
+    }
+}
@@ -8,7 +8,7 @@ use anyhow::{anyhow, bail, Result};
 use indexmap::{IndexMap, IndexSet};
 use serde::{Deserialize, Serialize};
 use turbo_tasks::{
-    primitives::{JsonValueVc, StringReadRef, StringVc, StringsVc, UsizeVc},
+    primitives::{JsonValueVc, OptionStringVc, StringReadRef, StringVc, StringsVc, UsizeVc},
     trace::TraceRawVcs,
     TryJoinIterExt, ValueToString, ValueToStringVc,
 };
@@ -1096,6 +1096,12 @@ pub enum EcmascriptExports {
 pub trait EcmascriptChunkPlaceable: ChunkableAsset + Asset {
     fn as_chunk_item(&self, context: ChunkingContextVc) -> EcmascriptChunkItemVc;
     fn get_exports(&self) -> EcmascriptExportsVc;
+    /// The module type that import assertions are checked against, e. g.
+    /// `json` for `assert { type: "json" }`. JavaScript modules don't have an
+    /// assertable type.
+    fn import_assertion_type(&self) -> OptionStringVc {
+        OptionStringVc::cell(None)
+    }
 }
 
 #[turbo_tasks::value(transparent)]
@@ -29,6 +29,10 @@ use chunk::{
 use code_gen::CodeGenerateableVc;
 use parse::{parse, ParseResult, ParseResultSourceMap};
 use path_visitor::ApplyVisitors;
+pub use references::esm::{
+    esm_reference_resolve_stats, warmup_esm_references, EsmAssetReference, EsmAssetReferenceVc,
+    EsmReferenceResolveStats,
+};
 use references::AnalyzeEcmascriptModuleResult;
 use swc_core::{
     common::GLOBALS,
@@ -5,14 +5,18 @@ use swc_core::{
     ecma::ast::{Expr, ExprStmt, Ident, Lit, Module, ModuleItem, Program, Script, Stmt},
     quote,
 };
-use turbo_tasks::{primitives::StringVc, Value, ValueToString, ValueToStringVc};
+use turbo_tasks::{
+    primitives::StringVc, registry, CompletionVc, NativeFunctionCacheStats, TryJoinIterExt, Value,
+    ValueToString, ValueToStringVc,
+};
 use turbopack_core::{
     asset::Asset,
     chunk::{
         ChunkableAssetReference, ChunkableAssetReferenceVc, ChunkingContextVc, ChunkingType,
         ChunkingTypeOptionVc, ModuleId,
     },
-    reference::{AssetReference, AssetReferenceVc},
+    issue::{analyze::AnalyzeIssue, IssueSeverity},
+    reference::{AssetReference, AssetReferenceVc, AssetReferencesVc},
     resolve::{
         origin::ResolveOriginVc, parse::RequestVc, ResolveResult, ResolveResultVc, SpecialType,
     },
@@ -58,6 +62,12 @@ pub struct EsmAssetReference {
     pub origin: ResolveOriginVc,
     pub request: RequestVc,
     pub annotations: ImportAnnotations,
+    /// The module type asserted by the import, e. g. `json` for `assert {
+    /// type: "json" }`
+    pub assert_type: Option<String>,
+    /// True, when none of the bindings of the imported module are used, e. g.
+    /// `import "./polyfill"`
+    pub side_effect_only: bool,
 }
 
 impl EsmAssetReference {
@@ -73,7 +83,7 @@ impl EsmAssetReference {
 #[turbo_tasks::value_impl]
 impl EsmAssetReferenceVc {
     #[turbo_tasks::function]
-    pub(super) async fn get_referenced_asset(self) -> Result<ReferencedAssetVc> {
+    pub async fn get_referenced_asset(self) -> Result<ReferencedAssetVc> {
         let this = self.await?;
         let resolve_result = esm_resolve(this.get_origin(), this.request);
         match &*resolve_result.await? {
@@ -92,6 +102,9 @@ impl EsmAssetReferenceVc {
         let assets = resolve_result.primary_assets();
         for asset in assets.await?.iter() {
             if let Some(placeable) = EcmascriptChunkPlaceableVc::resolve_from(asset).await? {
+                if let Some(ty) = &this.assert_type {
+                    check_assert_type(self, ty, placeable).await?;
+                }
                 return Ok(ReferencedAssetVc::cell(ReferencedAsset::Some(placeable)));
             }
         }
@@ -103,15 +116,57 @@ impl EsmAssetReferenceVc {
         origin: ResolveOriginVc,
         request: RequestVc,
         annotations: Value<ImportAnnotations>,
+        assert_type: Option<String>,
+        side_effect_only: bool,
     ) -> Self {
         Self::cell(EsmAssetReference {
             origin,
             request,
             annotations: annotations.into_value(),
+            assert_type,
+            side_effect_only,
         })
     }
 }
 
+/// Emits an issue when the resolved `asset` doesn't have the module type that
+/// is asserted by the import, e. g. when `import data from "./data" assert {
+/// type: "json" }` resolves to a JavaScript file.
+async fn check_assert_type(
+    reference: EsmAssetReferenceVc,
+    ty: &str,
+    asset: EcmascriptChunkPlaceableVc,
+) -> Result<()> {
+    let module_type = asset.import_assertion_type().await?;
+    let matches = match ty {
+        "json" => module_type.as_deref() == Some("json"),
+        // Other module types can't be checked yet
+        _ => true,
+    };
+    if !matches {
+        let this = reference.await?;
+        AnalyzeIssue {
+            code: None,
+            category: StringVc::cell("analyze".to_string()),
+            message: StringVc::cell(format!(
+                "The import {} asserts the module type \"{}\", but it resolved to {}, which isn't \
+                 of that type",
+                request_to_string(this.request).await?,
+                ty,
+                asset.path().to_string().await?
+            )),
+            path: this.origin.origin_path(),
+            severity: IssueSeverity::Error.into(),
+            source: None,
+            title: StringVc::cell("module type mismatch".to_string()),
+        }
+        .cell()
+        .as_issue()
+        .emit();
+    }
+    Ok(())
+}
+
 #[turbo_tasks::value_impl]
 impl AssetReference for EsmAssetReference {
     #[turbo_tasks::function]
@@ -120,6 +175,58 @@ impl AssetReference for EsmAssetReference {
     }
 }
 
+/// Cache counters for the resolution of [EsmAssetReference]s, see
+/// [esm_reference_resolve_stats].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EsmReferenceResolveStats {
+    /// Calls of `get_referenced_asset`, which is used by code generation.
+    pub referenced_asset: NativeFunctionCacheStats,
+    /// Calls of [AssetReference::resolve_reference].
+    pub resolve_reference: NativeFunctionCacheStats,
+}
+
+/// Returns how often resolving an [EsmAssetReference] has been served by an
+/// existing task instead of being recomputed. The counters are always zero
+/// without the `log_function_stats` feature of turbo-tasks.
+pub fn esm_reference_resolve_stats() -> EsmReferenceResolveStats {
+    let cache_stats = |path| {
+        registry::get_function_by_path(env!("CARGO_PKG_NAME"), path)
+            .map(|function| function.cache_stats())
+            .unwrap_or_default()
+    };
+    EsmReferenceResolveStats {
+        referenced_asset: cache_stats(
+            "references::esm::base::EsmAssetReferenceVc::get_referenced_asset",
+        ),
+        resolve_reference: cache_stats(
+            "references::esm::base::EsmAssetReference::AssetReference::resolve_reference",
+        ),
+    }
+}
+
+/// Resolves all `references` of a module concurrently, e. g. in a prewarm
+/// phase, so the resolved assets are already cached when the module is
+/// code generated. [EsmAssetReference]s are resolved the same way code
+/// generation resolves them, all other references via
+/// [AssetReference::resolve_reference].
+#[turbo_tasks::function]
+pub async fn warmup_esm_references(references: AssetReferencesVc) -> Result<CompletionVc> {
+    references
+        .await?
+        .iter()
+        .map(|reference| async move {
+            if let Some(esm) = EsmAssetReferenceVc::resolve_from(reference).await? {
+                esm.get_referenced_asset().await?;
+            } else {
+                reference.resolve_reference().await?;
+            }
+            Ok(()) as Result<_>
+        })
+        .try_join()
+        .await?;
+    Ok(CompletionVc::new())
+}
+
 #[turbo_tasks::value_impl]
 impl ValueToString for EsmAssetReference {
     #[turbo_tasks::function]
@@ -181,32 +288,44 @@ impl CodeGenerateable for EsmAssetReference {
 
         // separate chunks can't be imported as the modules are not available
         if !matches!(*chunking_type, None | Some(ChunkingType::Separate)) {
+            let side_effect_only = self_vc.await?.side_effect_only;
             let referenced_asset = self_vc.get_referenced_asset().await?;
             if let Some(ident) = referenced_asset.get_ident().await? {
                 match &*referenced_asset {
                     ReferencedAsset::Some(asset) => {
                         let id = asset.as_chunk_item(context).id().await?;
                         visitors.push(create_visitor!(visit_mut_program(program: &mut Program) {
-                            let stmt = quote!(
-                                "var $name = __turbopack_import__($id);" as Stmt,
-                                name = Ident::new(ident.clone().into(), DUMMY_SP),
-                                id: Expr = Expr::Lit(match &*id {
-                                    ModuleId::String(s) => s.clone().into(),
-                                    ModuleId::Number(n) => (*n as f64).into(),
-                                })
-                            );
+                            let id = Expr::Lit(match &*id {
+                                ModuleId::String(s) => s.clone().into(),
+                                ModuleId::Number(n) => (*n as f64).into(),
+                            });
+                            // Without any bindings there is no need to store the module
+                            let stmt = if side_effect_only {
+                                quote!("__turbopack_import__($id);" as Stmt, id: Expr = id)
+                            } else {
+                                quote!(
+                                    "var $name = __turbopack_import__($id);" as Stmt,
+                                    name = Ident::new(ident.clone().into(), DUMMY_SP),
+                                    id: Expr = id
+                                )
+                            };
                             insert_hoisted_stmt(program, stmt);
                         }));
                     }
                     ReferencedAsset::OriginalReferenceTypeExternal(request) => {
                         let request = request.clone();
                         visitors.push(create_visitor!(visit_mut_program(program: &mut Program) {
+                            let id = Expr::Lit(request.clone().into());
                             // TODO Technically this should insert a ESM external, but we don't support that yet
-                            let stmt = quote!(
-                                "var $name = __turbopack_external_require__($id);" as Stmt,
-                                name = Ident::new(ident.clone().into(), DUMMY_SP),
-                                id: Expr = Expr::Lit(request.clone().into())
-                            );
+                            let stmt = if side_effect_only {
+                                quote!("__turbopack_external_require__($id);" as Stmt, id: Expr = id)
+                            } else {
+                                quote!(
+                                    "var $name = __turbopack_external_require__($id);" as Stmt,
+                                    name = Ident::new(ident.clone().into(), DUMMY_SP),
+                                    id: Expr = id
+                                )
+                            };
                             insert_hoisted_stmt(program, stmt);
                         }));
                     }
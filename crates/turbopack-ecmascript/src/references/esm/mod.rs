@@ -6,7 +6,10 @@ pub(crate) mod module_id;
 pub(crate) mod module_item;
 
 pub use self::{
-    base::{EsmAssetReference, EsmAssetReferenceVc},
+    base::{
+        esm_reference_resolve_stats, warmup_esm_references, EsmAssetReference, EsmAssetReferenceVc,
+        EsmReferenceResolveStats,
+    },
     binding::{EsmBinding, EsmBindingVc},
     dynamic::{EsmAsyncAssetReference, EsmAsyncAssetReferenceVc},
     export::{EsmExports, EsmExportsVc},
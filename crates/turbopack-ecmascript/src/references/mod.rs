@@ -284,11 +284,13 @@ pub(crate) async fn analyze_ecmascript_module(
                 GLOBALS.set(globals, || {
                     let var_graph = create_graph(program, eval_context);
 
-                    for (src, annotations) in eval_context.imports.references() {
+                    for (i, (src, annotations)) in eval_context.imports.references().enumerate() {
                         let r = EsmAssetReferenceVc::new(
                             origin,
                             RequestVc::parse(Value::new(src.to_string().into())),
                             Value::new(annotations.clone()),
+                            eval_context.imports.assert_type(i).map(|ty| ty.to_string()),
+                            eval_context.imports.is_side_effect_only(i),
                         );
                         import_references.push(r);
                         analysis.add_reference(r);
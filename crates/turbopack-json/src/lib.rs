@@ -11,7 +11,10 @@ pub mod issue;
 
 use anyhow::Result;
 use issue::{JsonIssue, JsonIssueVc};
-use turbo_tasks::{primitives::StringVc, ValueToString, ValueToStringVc};
+use turbo_tasks::{
+    primitives::{OptionStringVc, StringVc},
+    ValueToString, ValueToStringVc,
+};
 use turbo_tasks_fs::FileSystemPathVc;
 use turbopack_core::{
     asset::{Asset, AssetContentVc, AssetVc},
@@ -75,6 +78,11 @@ impl EcmascriptChunkPlaceable for JsonModuleAsset {
         })
         .into()
     }
+
+    #[turbo_tasks::function]
+    fn import_assertion_type(&self) -> OptionStringVc {
+        OptionStringVc::cell(Some("json".to_string()))
+    }
 }
 
 #[turbo_tasks::value]
@@ -0,0 +1,3 @@
+import x from "./x.js" assert { type: "json" };
+
+console.log(x);
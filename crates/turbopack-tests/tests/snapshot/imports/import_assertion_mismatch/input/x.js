@@ -0,0 +1,3 @@
+export default function x() {
+  return "not json";
+}
@@ -0,0 +1,3 @@
+import "./polyfill";
+import { value } from "./bound";
+console.log(value);
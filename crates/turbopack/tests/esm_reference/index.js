@@ -0,0 +1,3 @@
+import { value } from "./dep.js";
+
+console.log(value);
@@ -0,0 +1,83 @@
+#![cfg(feature = "log_function_stats")]
+#![feature(min_specialization)]
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use turbo_tasks::{TurboTasks, Value};
+use turbo_tasks_fs::{DiskFileSystemVc, FileSystem};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack::{
+    register, resolve_options_context::ResolveOptionsContext, transition::TransitionsByNameVc,
+    ModuleAssetContextVc,
+};
+use turbopack_core::{
+    asset::Asset,
+    context::AssetContext,
+    environment::{EnvironmentIntention, EnvironmentVc, ExecutionEnvironment, NodeJsEnvironment},
+    reference::{AssetReference, AssetReferenceVc},
+    source_asset::SourceAssetVc,
+};
+use turbopack_ecmascript::{esm_reference_resolve_stats, EsmAssetReferenceVc};
+
+#[tokio::test]
+async fn resolving_a_reference_again_is_a_cache_hit() {
+    register();
+    include!(concat!(
+        env!("OUT_DIR"),
+        "/register_test_esm_reference_stats.rs"
+    ));
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.run_once(async {
+        let reference = esm_reference().await?;
+        reference.resolve_reference().await?;
+        let first = esm_reference_resolve_stats().resolve_reference;
+
+        // A different Vc that resolves to the same reference needs a new
+        // resolve task, which then calls the already cached implementation
+        same_reference(reference).resolve_reference().await?;
+        let second = esm_reference_resolve_stats().resolve_reference;
+
+        assert!(second.hits > first.hits);
+        assert_eq!(second.misses, first.misses);
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+/// Returns the ESM reference from `tests/esm_reference/index.js` to its
+/// dependency.
+async fn esm_reference() -> Result<AssetReferenceVc> {
+    let fs = DiskFileSystemVc::new(
+        "workspace".to_string(),
+        env!("CARGO_MANIFEST_DIR").to_string(),
+    );
+    let source = SourceAssetVc::new(fs.root().join("tests/esm_reference/index.js"));
+    let context = ModuleAssetContextVc::new(
+        TransitionsByNameVc::cell(HashMap::new()),
+        EnvironmentVc::new(
+            Value::new(ExecutionEnvironment::NodeJsLambda(
+                NodeJsEnvironment::default().into(),
+            )),
+            Value::new(EnvironmentIntention::ServerRendering),
+        ),
+        Default::default(),
+        ResolveOptionsContext::default().cell(),
+    );
+    let module = context.process(source.into());
+    for &reference in module.references().await?.iter() {
+        if EsmAssetReferenceVc::resolve_from(reference)
+            .await?
+            .is_some()
+        {
+            return Ok(reference);
+        }
+    }
+    None.context("index.js has no ESM reference")
+}
+
+#[turbo_tasks::function]
+fn same_reference(reference: AssetReferenceVc) -> AssetReferenceVc {
+    reference
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "log_function_stats")]
+#![feature(min_specialization)]
+
+use std::collections::HashMap;
+
+use turbo_tasks::{TurboTasks, Value};
+use turbo_tasks_fs::{DiskFileSystemVc, FileSystem};
+use turbo_tasks_memory::MemoryBackend;
+use turbopack::{
+    register, resolve_options_context::ResolveOptionsContext, transition::TransitionsByNameVc,
+    ModuleAssetContextVc,
+};
+use turbopack_core::{
+    asset::Asset,
+    context::AssetContext,
+    environment::{EnvironmentIntention, EnvironmentVc, ExecutionEnvironment, NodeJsEnvironment},
+    reference::AssetReferencesVc,
+    source_asset::SourceAssetVc,
+};
+use turbopack_ecmascript::{
+    esm_reference_resolve_stats, warmup_esm_references, EsmAssetReferenceVc,
+};
+
+#[tokio::test]
+async fn warmed_up_references_are_cache_hits() {
+    register();
+    let tt = TurboTasks::new(MemoryBackend::new());
+    tt.run_once(async {
+        let references = module_references();
+        warmup_esm_references(references).await?;
+        let mut esm_references = Vec::new();
+        for &reference in references.await?.iter() {
+            if let Some(esm) = EsmAssetReferenceVc::resolve_from(reference).await? {
+                esm_references.push(esm);
+            }
+        }
+        assert!(!esm_references.is_empty(), "index.js has no ESM reference");
+        let before = esm_reference_resolve_stats().referenced_asset;
+
+        for esm in esm_references {
+            esm.get_referenced_asset().await?;
+        }
+        let after = esm_reference_resolve_stats().referenced_asset;
+
+        assert!(after.hits > before.hits);
+        assert_eq!(after.misses, before.misses);
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+/// Returns the references of `tests/esm_reference/index.js`.
+fn module_references() -> AssetReferencesVc {
+    let fs = DiskFileSystemVc::new(
+        "workspace".to_string(),
+        env!("CARGO_MANIFEST_DIR").to_string(),
+    );
+    let source = SourceAssetVc::new(fs.root().join("tests/esm_reference/index.js"));
+    let context = ModuleAssetContextVc::new(
+        TransitionsByNameVc::cell(HashMap::new()),
+        EnvironmentVc::new(
+            Value::new(ExecutionEnvironment::NodeJsLambda(
+                NodeJsEnvironment::default().into(),
+            )),
+            Value::new(EnvironmentIntention::ServerRendering),
+        ),
+        Default::default(),
+        ResolveOptionsContext::default().cell(),
+    );
+    context.process(source.into()).references()
+}